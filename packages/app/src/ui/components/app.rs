@@ -1,17 +1,36 @@
 use std::rc::Rc;
 
 use closure::closure;
+use const_oid::ObjectIdentifier;
+use der::DecodePem;
+use js_sys::{Array, Uint8Array};
+use pkcs8::LineEnding;
+use time::OffsetDateTime;
+use wasm_bindgen::JsCast;
+use web_sys::{Blob, HtmlAnchorElement, Url};
 use x509_cert::name::Name;
+use x509_cert::Certificate;
 use yew::prelude::*;
 
 use crate::ui::components::basic::*;
 use crate::ui::components::*;
 use crate::ui::hooks::*;
 use gen::alt_name::AltName;
+use gen::certificate::TimeEncoding;
+use gen::csr::{gen_csr, CsrOptions};
+use gen::custom_extension::CustomExtension;
 use gen::duration::parse_duration_str;
-use gen::scheme::SignatureStrategy;
+use gen::key_id::KeyIdMethod;
+use gen::key_usage::{key_usage_from_bits, KeyPurpose, KeyUsageBit};
+use gen::name_constraints::Subtree;
+use gen::pfx::{gen_pfx, PfxOptions};
+use gen::policy::Policy;
+use gen::scheme::{load_key, SignatureStrategy};
 use gen::self_signed::gen_self_signed;
 use gen::self_signed::SelfSignedCertOptions;
+use gen::serial::{SerialStrategy, SerialStrategyType};
+
+const SERIAL_COUNTER_KEY: &str = "serial-counter";
 
 #[function_component]
 pub fn App() -> Html {
@@ -19,38 +38,246 @@ pub fn App() -> Html {
 
 	let subject: Slot<Name> = use_slot_with_default();
 	let duration: Slot<String> = use_slot_with_default();
+	let not_before: Slot<Option<OffsetDateTime>> = use_slot_with_default();
+	let not_after: Slot<Option<OffsetDateTime>> = use_slot_with_default();
+	let backdate: Slot<String> = use_slot_with_default();
+	let time_encoding: Slot<Option<TimeEncoding>> = use_slot_with_default();
+	let serial_strategy: Slot<Option<SerialStrategyType>> = use_slot_with_default();
+	let serial: Slot<String> = use_slot_with_default();
+
+	let is_ca: Slot<bool> = use_slot_with_default();
+	let path_len_constraint: Slot<String> = use_slot_with_default();
 
 	let san: Slot<Vec<AltName>> = use_slot_with_default();
+	let permitted_subtrees: Slot<Vec<Subtree>> = use_slot_with_default();
+	let excluded_subtrees: Slot<Vec<Subtree>> = use_slot_with_default();
+	let key_usage: Slot<Vec<KeyUsageBit>> = use_slot_with_default();
+	let key_usage_critical: Slot<bool> = use_slot(|| true);
+	let ext_key_usage: Slot<Vec<KeyPurpose>> = use_slot_with_default();
+	let key_id_method: Slot<Option<KeyIdMethod>> = use_slot_with_default();
+
+	let policy_oid: Slot<String> = use_slot_with_default();
+	let policy_cps_uri: Slot<String> = use_slot_with_default();
+
+	let ocsp_url: Slot<String> = use_slot_with_default();
+	let ca_issuers_url: Slot<String> = use_slot_with_default();
+	let crl_url: Slot<String> = use_slot_with_default();
+
+	let custom_extensions: Slot<Vec<CustomExtension>> = use_slot_with_default();
 
 	let key: Slot<String> = use_slot_with_default();
+	let key_passphrase: Slot<String> = use_slot_with_default();
 	let crt: Slot<String> = use_slot_with_default();
+	let csr: Slot<String> = use_slot_with_default();
+
+	let pfx_password: Slot<String> = use_slot_with_default();
+
+	let error: Slot<Option<String>> = use_slot_with_default();
+
+	let duration_value = parse_optional(&duration, parse_duration_str);
+	let backdate_value = parse_optional(&backdate, parse_duration_str);
+	let path_len_constraint_value = parse_optional(&path_len_constraint, parse_path_len_constraint);
+	let policy_oid_value = parse_optional(&policy_oid, parse_oid);
+	let serial_value = parse_optional(&serial, parse_serial);
+
+	let backdate_offset = backdate_value
+		.clone()
+		.ok()
+		.flatten()
+		.map(|backdate| backdate.to_std_duration())
+		.unwrap_or_default();
+	let validate_time = |date_time: OffsetDateTime| {
+		time_encoding
+			.unwrap_or_default()
+			.validate(date_time)
+			.err()
+			.map(|err| err.to_string())
+	};
+	let not_before_error =
+		not_before.and_then(|not_before| validate_time(not_before - backdate_offset));
+	let not_after_error = not_after.and_then(|not_after| match *not_before {
+		Some(not_before) if not_after <= not_before - backdate_offset => {
+			Some("Not After must be later than Not Before".to_owned())
+		}
+		_ => validate_time(not_after),
+	});
 
 	let onclick_generate = closure!(
 		clone scheme,
 		clone subject,
 		clone duration,
+		clone not_before,
+		clone not_after,
+		clone backdate,
+		clone time_encoding,
+		clone serial_strategy,
+		clone serial,
+		clone is_ca,
+		clone path_len_constraint,
 		clone san,
+		clone permitted_subtrees,
+		clone excluded_subtrees,
+		clone key_usage,
+		clone key_usage_critical,
+		clone ext_key_usage,
+		clone key_id_method,
+		clone policy_oid,
+		clone policy_cps_uri,
+		clone ocsp_url,
+		clone ca_issuers_url,
+		clone crl_url,
+		clone custom_extensions,
 		clone key,
 		clone crt,
+		clone error,
 		|_| {
+			let (Ok(duration), Ok(backdate), Ok(path_len_constraint), Ok(policy_oid)) = (
+				parse_optional(&duration, parse_duration_str),
+				parse_optional(&backdate, parse_duration_str),
+				parse_optional(&path_len_constraint, parse_path_len_constraint),
+				parse_optional(&policy_oid, parse_oid),
+			) else {
+				return;
+			};
+
 			if let Some(scheme) = (*scheme).as_ref() {
-				let pair = gen_self_signed(
+				let serial = match serial_strategy.unwrap_or_default() {
+					SerialStrategyType::Random => Ok(SerialStrategy::Random),
+					SerialStrategyType::Counter => next_serial_counter()
+						.map(|counter| SerialStrategy::Provided(counter.to_be_bytes().to_vec())),
+					SerialStrategyType::Timestamp => Ok(SerialStrategy::Timestamp),
+					SerialStrategyType::Provided => parse_serial(&serial),
+				};
+
+				let serial = match serial {
+					Ok(serial) => serial,
+					Err(err) => {
+						error.set(Some(err));
+						return;
+					}
+				};
+
+				let result = gen_self_signed(
 					scheme.as_ref(),
 					SelfSignedCertOptions {
 						issuer: subject.get(),
 						subject: subject.get(),
-						duration: parse_duration_str(&duration).unwrap(),
+						duration,
+						not_before: not_before.get(),
+						not_after: not_after.get(),
+						backdate: backdate
+							.map(|backdate| backdate.to_std_duration())
+							.unwrap_or_default(),
+						time_encoding: time_encoding.unwrap_or_default(),
+						serial,
+						is_ca: is_ca.get(),
+						path_len_constraint,
+						san: (*san).clone(),
+						key_usage: (!key_usage.is_empty())
+							.then(|| key_usage_from_bits(key_usage.iter().copied())),
+						key_usage_critical: Some(key_usage_critical.get()),
+						ext_key_usage: ext_key_usage.iter().copied().map(Into::into).collect(),
+						permitted_subtrees: (*permitted_subtrees).clone(),
+						excluded_subtrees: (*excluded_subtrees).clone(),
+						policies: policy_oid
+							.map(|oid| Policy {
+								oid,
+								cps_uri: (!policy_cps_uri.is_empty()).then(|| policy_cps_uri.get()),
+								user_notice: None,
+							})
+							.into_iter()
+							.collect(),
+						ca_policy: None,
+						ocsp_urls: (!ocsp_url.is_empty()).then(|| ocsp_url.get()).into_iter().collect(),
+						ca_issuers_urls: (!ca_issuers_url.is_empty())
+							.then(|| ca_issuers_url.get())
+							.into_iter()
+							.collect(),
+						crl_urls: (!crl_url.is_empty()).then(|| crl_url.get()).into_iter().collect(),
+						custom_extensions: (*custom_extensions).clone(),
+						key_id_method: key_id_method.unwrap_or_default(),
+					},
+				);
+
+				match result {
+					Ok(pair) => {
+						key.set(pair.0);
+						crt.set(pair.1);
+						error.set(None);
+					}
+					Err(err) => error.set(Some(err.to_string())),
+				}
+			}
+		}
+	);
+
+	let onclick_generate_csr = closure!(
+		clone scheme,
+		clone subject,
+		clone san,
+		clone key,
+		clone csr,
+		|_| {
+			if let Some(scheme) = (*scheme).as_ref() {
+				let pair = gen_csr(
+					scheme.as_ref(),
+					CsrOptions {
+						subject: subject.get(),
 						san: (*san).clone(),
 					},
 				)
 				.unwrap();
 
 				key.set(pair.0);
-				crt.set(pair.1);
+				csr.set(pair.1);
 			}
 		}
 	);
 
+	let onclick_download_key = closure!(
+		clone key,
+		clone key_passphrase,
+		|_| {
+			if key.is_empty() || key_passphrase.is_empty() {
+				return;
+			}
+
+			let (_, private_key) = load_key(key.as_bytes()).unwrap();
+			let encrypted_key = private_key
+				.to_encrypted_pem(&key_passphrase, LineEnding::LF)
+				.unwrap();
+
+			download("private_key.pem", encrypted_key.as_bytes());
+		}
+	);
+
+	let onclick_download_pfx = closure!(
+		clone key,
+		clone crt,
+		clone pfx_password,
+		|_| {
+			if key.is_empty() || crt.is_empty() {
+				return;
+			}
+
+			let (_, private_key) = load_key(key.as_bytes()).unwrap();
+			let certificate = Certificate::from_pem(crt.as_bytes()).unwrap();
+
+			let pfx = gen_pfx(
+				private_key.as_ref(),
+				&certificate,
+				PfxOptions {
+					friendly_name: Some(certificate.tbs_certificate.subject.to_string()),
+					password: pfx_password.get(),
+					chain: Vec::new(),
+				},
+			)
+			.unwrap();
+
+			download("certificate.p12", &pfx);
+		}
+	);
+
 	html! {
 		<div>
 			<Scheme onchange={ scheme.change_handler() } />
@@ -59,12 +286,195 @@ pub fn App() -> Html {
 				<span>{ "Duration" }</span>
 				<Input slot={ duration } />
 			</label>
+			if let Err(error) = duration_value {
+				<p>{ error }</p>
+			}
+			<label>
+				<span>{ "Not Before" }</span>
+				<DateTimeInput slot={ not_before } />
+			</label>
+			if let Some(error) = not_before_error {
+				<p>{ error }</p>
+			}
+			<label>
+				<span>{ "Not After" }</span>
+				<DateTimeInput slot={ not_after } />
+			</label>
+			if let Some(error) = not_after_error {
+				<p>{ error }</p>
+			}
+			<label>
+				<span>{ "Backdate" }</span>
+				<Input slot={ backdate } />
+			</label>
+			if let Err(error) = backdate_value {
+				<p>{ error }</p>
+			}
+			<label>
+				<span>{ "Time Encoding" }</span>
+				<Select<TimeEncoding> slot={ time_encoding } />
+			</label>
+			<label>
+				<span>{ "Serial Number" }</span>
+				<Select<SerialStrategyType> slot={ serial_strategy.clone() } />
+			</label>
+			if *serial_strategy == Some(SerialStrategyType::Provided) {
+				<label>
+					<span>{ "Serial Number (hex)" }</span>
+					<Input slot={ serial } />
+				</label>
+				if let Err(error) = serial_value {
+					<p>{ error }</p>
+				}
+			}
+			<label>
+				<span>{ "Certificate Authority" }</span>
+				<Checkbox slot={ is_ca } />
+			</label>
+			<label>
+				<span>{ "Path Length" }</span>
+				<Input slot={ path_len_constraint } />
+			</label>
+			if let Err(error) = path_len_constraint_value {
+				<p>{ error }</p>
+			}
 			<SANList slot={ san } />
+			<SubtreeList title="Permitted Subtrees" slot={ permitted_subtrees } />
+			<SubtreeList title="Excluded Subtrees" slot={ excluded_subtrees } />
+			<label>
+				<span>{ "Key Usage" }</span>
+				<MultiSelect<KeyUsageBit> slot={ key_usage } />
+			</label>
+			<label>
+				<span>{ "Key Usage Critical" }</span>
+				<Checkbox slot={ key_usage_critical } />
+			</label>
+			<label>
+				<span>{ "Extended Key Usage" }</span>
+				<MultiSelect<KeyPurpose> slot={ ext_key_usage } />
+			</label>
+			<label>
+				<span>{ "Key Identifier" }</span>
+				<Select<KeyIdMethod> slot={ key_id_method } />
+			</label>
+			<label>
+				<span>{ "Certificate Policy" }</span>
+				<Input slot={ policy_oid } />
+			</label>
+			if let Err(error) = policy_oid_value {
+				<p>{ error }</p>
+			}
+			<label>
+				<span>{ "CPS URI" }</span>
+				<Input slot={ policy_cps_uri } />
+			</label>
+			<label>
+				<span>{ "OCSP URL" }</span>
+				<Input slot={ ocsp_url } />
+			</label>
+			<label>
+				<span>{ "CA Issuers URL" }</span>
+				<Input slot={ ca_issuers_url } />
+			</label>
+			<label>
+				<span>{ "CRL URL" }</span>
+				<Input slot={ crl_url } />
+			</label>
+			<ExtensionList slot={ custom_extensions } />
 			<div>
 				<button onclick={onclick_generate}>{ "Generate" }</button>
+				<button onclick={onclick_generate_csr}>{ "Generate CSR" }</button>
+			</div>
+			if let Some(error) = error.as_ref() {
+				<p>{ error }</p>
+			}
+			<Output title="Private Key" slot={ key.clone() } />
+			<div>
+				<label>
+					<span>{ "Private Key Passphrase" }</span>
+					<Input slot={ key_passphrase } password=true />
+				</label>
+				<button onclick={onclick_download_key}>{ "Download Encrypted Key" }</button>
 			</div>
-			<Output title="Private Key" slot={ key } />
 			<Output title="Certificate" slot={ crt } />
+			<Output title="Certificate Request" slot={ csr } />
+			<div>
+				<label>
+					<span>{ "PKCS#12 Password" }</span>
+					<Input slot={ pfx_password } password=true />
+				</label>
+				<button onclick={onclick_download_pfx}>{ "Download PKCS#12" }</button>
+			</div>
 		</div>
 	}
 }
+
+fn parse_optional<T, E: ToString>(
+	value: &str,
+	parse: impl FnOnce(&str) -> Result<T, E>,
+) -> Result<Option<T>, String> {
+	if value.is_empty() {
+		return Ok(None);
+	}
+
+	parse(value).map(Some).map_err(|err| err.to_string())
+}
+
+fn parse_path_len_constraint(value: &str) -> Result<u8, String> {
+	value
+		.trim()
+		.parse()
+		.map_err(|_| format!("invalid path length {value}, expected a number from 0 to 255"))
+}
+
+fn parse_oid(value: &str) -> Result<ObjectIdentifier, String> {
+	ObjectIdentifier::new(value.trim()).map_err(|_| format!("invalid object identifier {value}"))
+}
+
+fn parse_serial(value: &str) -> Result<SerialStrategy, String> {
+	SerialStrategy::parse_provided(value.trim())
+		.map_err(|err| format!("invalid serial number {value}: {err}"))
+}
+
+fn download(filename: &str, bytes: &[u8]) {
+	let parts = Array::of1(&Uint8Array::from(bytes));
+	let blob = Blob::new_with_u8_array_sequence(&parts).unwrap();
+	let url = Url::create_object_url_with_blob(&blob).unwrap();
+
+	let anchor = web_sys::window()
+		.unwrap()
+		.document()
+		.unwrap()
+		.create_element("a")
+		.unwrap()
+		.dyn_into::<HtmlAnchorElement>()
+		.unwrap();
+
+	anchor.set_href(&url);
+	anchor.set_download(filename);
+	anchor.click();
+
+	Url::revoke_object_url(&url).unwrap();
+}
+
+fn next_serial_counter() -> Result<u128, String> {
+	let storage = web_sys::window()
+		.and_then(|window| window.local_storage().ok().flatten())
+		.ok_or("local storage is unavailable for the serial counter")?;
+
+	let current = match storage.get_item(SERIAL_COUNTER_KEY) {
+		Ok(Some(counter)) => counter
+			.parse::<u128>()
+			.map_err(|_| format!("invalid serial counter {counter} in local storage"))?,
+		Ok(None) => 0,
+		Err(_) => return Err("failed to read the serial counter".to_owned()),
+	};
+
+	let next = current.checked_add(1).ok_or("serial counter overflow")?;
+
+	storage
+		.set_item(SERIAL_COUNTER_KEY, &next.to_string())
+		.map_err(|_| "failed to store the serial counter")?;
+
+	Ok(next)
+}
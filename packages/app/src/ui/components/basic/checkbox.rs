@@ -0,0 +1,31 @@
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::ui::hooks::Slot;
+
+#[derive(Properties, PartialEq)]
+pub struct CheckboxProps {
+	#[prop_or_default]
+	pub slot: Option<Slot<bool>>,
+}
+
+#[function_component]
+pub fn Checkbox(props: &CheckboxProps) -> Html {
+	let (checked, onchange) = props
+		.slot
+		.clone()
+		.map(|slot| {
+			let callback = slot.change_handler();
+
+			(slot.get(), move |evt: Event| {
+				let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+
+				callback.emit(target.checked());
+			})
+		})
+		.unzip();
+
+	html! {
+		<input type="checkbox" checked={checked.unwrap_or_default()} {onchange} />
+	}
+}
@@ -0,0 +1,71 @@
+use js_sys::Date;
+use time::OffsetDateTime;
+use wasm_bindgen::JsValue;
+use web_sys::HtmlInputElement;
+use yew::prelude::*;
+
+use crate::ui::hooks::Slot;
+
+#[derive(Properties, PartialEq)]
+pub struct DateTimeInputProps {
+	#[prop_or_default]
+	pub slot: Option<Slot<Option<OffsetDateTime>>>,
+}
+
+#[function_component]
+pub fn DateTimeInput(props: &DateTimeInputProps) -> Html {
+	let (value, onchange) = props
+		.slot
+		.clone()
+		.map(|slot| {
+			let callback = slot.change_handler();
+
+			(slot.get().map(to_input_value), move |evt: Event| {
+				let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+				let value = target.value();
+
+				if value.is_empty() {
+					target.set_custom_validity("");
+					callback.emit(None);
+					return;
+				}
+
+				match from_input_value(&value) {
+					Some(date_time) => {
+						target.set_custom_validity("");
+						callback.emit(Some(date_time));
+					}
+					None => target.set_custom_validity("invalid date and time"),
+				}
+			})
+		})
+		.unzip();
+
+	html! {
+		<input type="datetime-local" value={ value.flatten() } {onchange} />
+	}
+}
+
+fn from_input_value(value: &str) -> Option<OffsetDateTime> {
+	let millis = Date::new(&JsValue::from_str(value)).get_time();
+
+	if millis.is_nan() {
+		return None;
+	}
+
+	OffsetDateTime::from_unix_timestamp_nanos(millis as i128 * 1_000_000).ok()
+}
+
+fn to_input_value(date_time: OffsetDateTime) -> String {
+	let millis = (date_time.unix_timestamp_nanos() / 1_000_000) as f64;
+	let date = Date::new(&JsValue::from_f64(millis));
+
+	format!(
+		"{:04}-{:02}-{:02}T{:02}:{:02}",
+		date.get_full_year(),
+		date.get_month() + 1,
+		date.get_date(),
+		date.get_hours(),
+		date.get_minutes()
+	)
+}
@@ -7,6 +7,8 @@ use crate::ui::hooks::Slot;
 pub struct InputProps {
 	#[prop_or_default]
 	pub slot: Option<Slot<String>>,
+	#[prop_or_default]
+	pub password: bool,
 }
 
 #[function_component]
@@ -26,6 +28,6 @@ pub fn Input(props: &InputProps) -> Html {
 		.unzip();
 
 	html! {
-		<input {value} {onchange} />
+		<input type={ props.password.then_some("password") } {value} {onchange} />
 	}
 }
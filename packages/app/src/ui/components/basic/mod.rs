@@ -1,5 +1,14 @@
+mod checkbox;
+pub use checkbox::*;
+
+mod date_time_input;
+pub use date_time_input::*;
+
 mod input;
 pub use input::*;
 
+mod multi_select;
+pub use multi_select::*;
+
 mod select;
 pub use select::*;
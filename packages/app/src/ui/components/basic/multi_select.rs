@@ -0,0 +1,66 @@
+use closure::closure;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlOptionElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use crate::ui::components::basic::Selectable;
+use crate::ui::hooks::Slot;
+
+#[derive(Properties, PartialEq)]
+pub struct MultiSelectProps<T: Selectable> {
+	#[prop_or_default]
+	pub slot: Option<Slot<Vec<T>>>,
+}
+
+#[function_component]
+pub fn MultiSelect<T: Selectable>(props: &MultiSelectProps<T>) -> Html {
+	let items: Vec<_> = enum_iterator::all::<T>().collect();
+
+	let (value, onchange) = props
+		.slot
+		.clone()
+		.map(closure!(clone items, |slot| {
+			let callback = slot.change_handler();
+
+			(slot.get(), move |evt: Event| {
+				let target = evt.target_dyn_into::<HtmlSelectElement>().unwrap();
+				let selected = target.selected_options();
+
+				let value = (0..selected.length())
+					.map(|index| {
+						let option = selected
+							.get_with_index(index)
+							.unwrap()
+							.dyn_into::<HtmlOptionElement>()
+							.unwrap();
+
+						items[option.value().parse::<usize>().unwrap()]
+					})
+					.collect();
+
+				callback.emit(value);
+			})
+		}))
+		.unzip();
+
+	let value = value.unwrap_or_default();
+
+	html! {
+		<select multiple=true {onchange}>
+			{
+				for items
+					.clone()
+					.into_iter()
+					.enumerate()
+					.map(|(index, item)| {
+						html!(
+							<option value={ index.to_string() }
+								selected={ value.contains(&item) }>
+								{ format!("{}", item) }
+							</option>
+						)
+					})
+			}
+		</select>
+	}
+}
@@ -37,9 +37,9 @@ pub fn Select<T: Selectable>(props: &SelectProps<T>) -> Html {
 					.dyn_into::<HtmlOptionElement>()
 					.unwrap();
 
-				let value = usize::from_str_radix(&selected.value(), 10).unwrap();
+				let value = selected.value().parse::<usize>().unwrap();
 
-				callback.emit(if value == 0 { None } else { Some(items.get(value - 1).unwrap().clone()) });
+				callback.emit(if value == 0 { None } else { Some(*items.get(value - 1).unwrap()) });
 			})
 		}))
 		.unzip();
@@ -0,0 +1,225 @@
+use std::str::FromStr;
+
+use enum_iterator::all;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlOptionElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use gen::custom_extension::{CustomExtension, ExtensionValueFormat};
+
+use crate::ui::hooks::*;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct ExtensionListProps {
+	#[prop_or_default]
+	pub slot: Option<Slot<Vec<CustomExtension>>>,
+}
+
+#[derive(Debug)]
+pub struct ExtensionList {
+	list: Vec<CustomExtension>,
+	to_add_oid: String,
+	to_add_critical: bool,
+	to_add_format: ExtensionValueFormat,
+	to_add_value: String,
+	error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum ExtensionListMessage {
+	UpdateToAddOid(String),
+	UpdateToAddCritical(bool),
+	UpdateToAddFormat(ExtensionValueFormat),
+	UpdateToAddValue(String),
+	Add,
+	Remove(usize),
+}
+
+impl ExtensionList {
+	fn update_slot(&self, ctx: &Context<Self>) {
+		if let Some(slot) = ctx.props().slot.as_ref() {
+			slot.set(self.list.clone())
+		}
+	}
+}
+
+impl Component for ExtensionList {
+	type Properties = ExtensionListProps;
+	type Message = ExtensionListMessage;
+
+	fn create(ctx: &Context<Self>) -> Self {
+		Self {
+			list: ctx
+				.props()
+				.slot
+				.clone()
+				.map(|s| s.get())
+				.unwrap_or_default(),
+			to_add_oid: String::default(),
+			to_add_critical: false,
+			to_add_format: ExtensionValueFormat::default(),
+			to_add_value: String::default(),
+			error: None,
+		}
+	}
+
+	fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+		match msg {
+			ExtensionListMessage::UpdateToAddOid(value) => {
+				self.to_add_oid = value;
+				false
+			}
+			ExtensionListMessage::UpdateToAddCritical(value) => {
+				self.to_add_critical = value;
+				false
+			}
+			ExtensionListMessage::UpdateToAddFormat(value) => {
+				self.to_add_format = value;
+				false
+			}
+			ExtensionListMessage::UpdateToAddValue(value) => {
+				self.to_add_value = value;
+				false
+			}
+			ExtensionListMessage::Add => {
+				match CustomExtension::parse(
+					&self.to_add_oid,
+					self.to_add_critical,
+					self.to_add_format,
+					&self.to_add_value,
+				) {
+					Ok(extension) => {
+						self.list.push(extension);
+						self.error = None;
+
+						self.update_slot(ctx);
+					}
+					Err(err) => self.error = Some(err.to_string()),
+				}
+
+				true
+			}
+			ExtensionListMessage::Remove(index) => {
+				self.list.remove(index);
+
+				self.update_slot(ctx);
+
+				true
+			}
+		}
+	}
+
+	fn view(&self, ctx: &Context<Self>) -> Html {
+		let onchange_to_add_oid = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+
+			ExtensionListMessage::UpdateToAddOid(target.value())
+		});
+
+		let onchange_to_add_critical = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+
+			ExtensionListMessage::UpdateToAddCritical(target.checked())
+		});
+
+		let onchange_to_add_format = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlSelectElement>().unwrap();
+			let selected = target
+				.selected_options()
+				.item(0)
+				.unwrap()
+				.dyn_into::<HtmlOptionElement>()
+				.unwrap();
+
+			let index = usize::from_str(&selected.value()).unwrap();
+
+			ExtensionListMessage::UpdateToAddFormat(
+				all::<ExtensionValueFormat>().nth(index).unwrap(),
+			)
+		});
+
+		let onchange_to_add_value = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+
+			ExtensionListMessage::UpdateToAddValue(target.value())
+		});
+
+		let onclick_add = ctx.link().callback(|_| ExtensionListMessage::Add);
+
+		let onclick_remove = ctx
+			.link()
+			.callback(|index: usize| ExtensionListMessage::Remove(index));
+
+		html! {
+			<div>
+				<span>{ "Advanced Extensions" }</span>
+				<ul>
+					{
+						for self.list.iter()
+							.enumerate()
+							.map(|(index, extension)| {
+								let onclick_remove = onclick_remove.clone();
+
+								html!(
+									<ExtensionItem extension={ extension.clone() } onremove={ move |_| { onclick_remove.emit(index) } } />
+								)
+							})
+					}
+				</ul>
+				<div>
+					<input placeholder="OID" value={ self.to_add_oid.clone() } onchange={ onchange_to_add_oid } />
+					<label>
+						<input type="checkbox" checked={ self.to_add_critical } onchange={ onchange_to_add_critical } />
+						<span>{ "Critical" }</span>
+					</label>
+					<select onchange={ onchange_to_add_format }>
+						{ for all::<ExtensionValueFormat>().enumerate().map(|(index, format)| {
+							html!(
+								<option value={ index.to_string() }
+									selected={ self.to_add_format == format }>
+									{ format!("{}", format) }
+								</option>
+							)
+						}) }
+					</select>
+					<input value={ self.to_add_value.clone() } onchange={ onchange_to_add_value } />
+					<button onclick={ onclick_add }>{ "Add" }</button>
+				</div>
+				if let Some(error) = self.error.as_ref() {
+					<p>{ error }</p>
+				}
+			</div>
+		}
+	}
+}
+
+#[derive(Debug, Properties, PartialEq)]
+struct ExtensionItemProps {
+	extension: CustomExtension,
+	onremove: Callback<()>,
+}
+
+#[function_component]
+fn ExtensionItem(props: &ExtensionItemProps) -> Html {
+	let ExtensionItemProps {
+		extension,
+		onremove,
+	} = props;
+	let onremove = onremove.clone();
+
+	let value = extension
+		.value
+		.iter()
+		.map(|byte| format!("{byte:02X}"))
+		.collect::<Vec<_>>()
+		.join(":");
+
+	html!(
+		<li>
+			<p>{ extension.oid.to_string() }</p>
+			<p>{ if extension.critical { "Critical" } else { "Non-critical" } }</p>
+			<p>{ value }</p>
+			<button onclick={ move |_| { onremove.emit(()) } }>{ "Remove" }</button>
+		</li>
+	)
+}
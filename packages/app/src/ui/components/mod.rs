@@ -3,6 +3,9 @@ pub mod basic;
 mod app;
 pub use app::*;
 
+mod extension_list;
+pub use extension_list::*;
+
 mod output;
 pub use output::*;
 
@@ -14,3 +17,6 @@ pub use scheme::*;
 
 mod subject;
 pub use subject::*;
+
+mod subtree_list;
+pub use subtree_list::*;
@@ -21,6 +21,7 @@ pub struct SANList {
 	list: Vec<AltName>,
 	to_add_type: AltNameType,
 	to_add_value: String,
+	error: Option<String>,
 }
 
 #[derive(Debug)]
@@ -45,25 +46,31 @@ impl Component for SANList {
 				.unwrap_or_default(),
 			to_add_type: AltNameType::default(),
 			to_add_value: String::default(),
+			error: None,
 		}
 	}
 
 	fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
 		match msg {
 			SANListMessage::UpdateToAddType(value) => {
-				self.to_add_type = value.clone();
+				self.to_add_type = value;
 				false
 			}
 			SANListMessage::UpdateToAddValue(value) => {
-				self.to_add_value = value.clone();
+				self.to_add_value = value;
 				false
 			}
 			SANListMessage::Add => {
-				self.list
-					.push(AltName::parse(self.to_add_type, &self.to_add_value));
-
-				if let Some(slot) = ctx.props().slot.as_ref() {
-					slot.set(self.list.clone())
+				match AltName::parse(self.to_add_type, &self.to_add_value) {
+					Ok(alt_name) => {
+						self.list.push(alt_name);
+						self.error = None;
+
+						if let Some(slot) = ctx.props().slot.as_ref() {
+							slot.set(self.list.clone())
+						}
+					}
+					Err(err) => self.error = Some(err.to_string()),
 				}
 
 				true
@@ -134,6 +141,9 @@ impl Component for SANList {
 					<input value={ self.to_add_value.clone() } onchange={ onchange_to_add_value } />
 					<button onclick={ onclick_add }>{ "Add" }</button>
 				</div>
+				if let Some(error) = self.error.as_ref() {
+					<p>{ error }</p>
+				}
 			</div>
 		}
 	}
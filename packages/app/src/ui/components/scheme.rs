@@ -4,12 +4,21 @@ use enum_iterator::Sequence;
 use parse_display::Display;
 use yew::prelude::*;
 
-use gen::scheme::ecdsa::{EcdsaK256, EcdsaP256, EcdsaP384};
+use gen::scheme::ecdsa::{
+	EcdsaBrainpoolP256, EcdsaBrainpoolP384, EcdsaBrainpoolP512, EcdsaK256, EcdsaP256, EcdsaP384,
+	EcdsaP521,
+};
 use gen::scheme::ed25519::Ed25519;
 use gen::scheme::rsa::{
 	Rsa2048WithSha256, Rsa2048WithSha384, Rsa2048WithSha512, Rsa4096WithSha256, Rsa4096WithSha384,
 	Rsa4096WithSha512,
 };
+use gen::scheme::rsa_pss::{
+	Rsa2048PssRestrictedWithSha256, Rsa2048PssRestrictedWithSha384, Rsa2048PssRestrictedWithSha512,
+	Rsa2048PssWithSha256, Rsa2048PssWithSha384, Rsa2048PssWithSha512,
+	Rsa4096PssRestrictedWithSha256, Rsa4096PssRestrictedWithSha384, Rsa4096PssRestrictedWithSha512,
+	Rsa4096PssWithSha256, Rsa4096PssWithSha384, Rsa4096PssWithSha512,
+};
 use gen::scheme::{new_scheme, SignatureStrategy};
 
 use crate::ui::components::basic::*;
@@ -48,8 +57,16 @@ enum SchemeName {
 	EcdsaP256,
 	#[display("ECDSA using P-384 with SHA-384")]
 	EcdsaP384,
+	#[display("ECDSA using P-521 with SHA-512")]
+	EcdsaP521,
 	#[display("ECDSA using K-256 curve with SHA-256")]
 	EcdsaK256,
+	#[display("ECDSA using brainpoolP256r1 with SHA-256")]
+	EcdsaBrainpoolP256,
+	#[display("ECDSA using brainpoolP384r1 with SHA-384")]
+	EcdsaBrainpoolP384,
+	#[display("ECDSA using brainpoolP512r1 with SHA-512")]
+	EcdsaBrainpoolP512,
 	#[display("RSA using 2048 bit key with SHA-256")]
 	Rsa2048WithSha256,
 	#[display("RSA using 4096 bit key with SHA-256")]
@@ -62,21 +79,73 @@ enum SchemeName {
 	Rsa2048WithSha512,
 	#[display("RSA using 4096 bit key with SHA-512")]
 	Rsa4096WithSha512,
+	#[display("RSA-PSS using 2048 bit key with SHA-256")]
+	Rsa2048PssWithSha256,
+	#[display("RSA-PSS using 4096 bit key with SHA-256")]
+	Rsa4096PssWithSha256,
+	#[display("RSA-PSS using 2048 bit key with SHA-384")]
+	Rsa2048PssWithSha384,
+	#[display("RSA-PSS using 4096 bit key with SHA-384")]
+	Rsa4096PssWithSha384,
+	#[display("RSA-PSS using 2048 bit key with SHA-512")]
+	Rsa2048PssWithSha512,
+	#[display("RSA-PSS using 4096 bit key with SHA-512")]
+	Rsa4096PssWithSha512,
+	#[display("RSA-PSS using 2048 bit PSS-restricted key with SHA-256")]
+	Rsa2048PssRestrictedWithSha256,
+	#[display("RSA-PSS using 4096 bit PSS-restricted key with SHA-256")]
+	Rsa4096PssRestrictedWithSha256,
+	#[display("RSA-PSS using 2048 bit PSS-restricted key with SHA-384")]
+	Rsa2048PssRestrictedWithSha384,
+	#[display("RSA-PSS using 4096 bit PSS-restricted key with SHA-384")]
+	Rsa4096PssRestrictedWithSha384,
+	#[display("RSA-PSS using 2048 bit PSS-restricted key with SHA-512")]
+	Rsa2048PssRestrictedWithSha512,
+	#[display("RSA-PSS using 4096 bit PSS-restricted key with SHA-512")]
+	Rsa4096PssRestrictedWithSha512,
 }
 
 impl SchemeName {
-	fn into_scheme(&self) -> Rc<dyn SignatureStrategy> {
+	fn into_scheme(self) -> Rc<dyn SignatureStrategy> {
 		match self {
 			SchemeName::Ed25519 => new_scheme::<Ed25519>(),
 			SchemeName::EcdsaP256 => new_scheme::<EcdsaP256>(),
 			SchemeName::EcdsaP384 => new_scheme::<EcdsaP384>(),
+			SchemeName::EcdsaP521 => new_scheme::<EcdsaP521>(),
 			SchemeName::EcdsaK256 => new_scheme::<EcdsaK256>(),
+			SchemeName::EcdsaBrainpoolP256 => new_scheme::<EcdsaBrainpoolP256>(),
+			SchemeName::EcdsaBrainpoolP384 => new_scheme::<EcdsaBrainpoolP384>(),
+			SchemeName::EcdsaBrainpoolP512 => new_scheme::<EcdsaBrainpoolP512>(),
 			SchemeName::Rsa2048WithSha256 => new_scheme::<Rsa2048WithSha256>(),
 			SchemeName::Rsa4096WithSha256 => new_scheme::<Rsa4096WithSha256>(),
 			SchemeName::Rsa2048WithSha384 => new_scheme::<Rsa2048WithSha384>(),
 			SchemeName::Rsa4096WithSha384 => new_scheme::<Rsa4096WithSha384>(),
 			SchemeName::Rsa2048WithSha512 => new_scheme::<Rsa2048WithSha512>(),
 			SchemeName::Rsa4096WithSha512 => new_scheme::<Rsa4096WithSha512>(),
+			SchemeName::Rsa2048PssWithSha256 => new_scheme::<Rsa2048PssWithSha256>(),
+			SchemeName::Rsa4096PssWithSha256 => new_scheme::<Rsa4096PssWithSha256>(),
+			SchemeName::Rsa2048PssWithSha384 => new_scheme::<Rsa2048PssWithSha384>(),
+			SchemeName::Rsa4096PssWithSha384 => new_scheme::<Rsa4096PssWithSha384>(),
+			SchemeName::Rsa2048PssWithSha512 => new_scheme::<Rsa2048PssWithSha512>(),
+			SchemeName::Rsa4096PssWithSha512 => new_scheme::<Rsa4096PssWithSha512>(),
+			SchemeName::Rsa2048PssRestrictedWithSha256 => {
+				new_scheme::<Rsa2048PssRestrictedWithSha256>()
+			}
+			SchemeName::Rsa4096PssRestrictedWithSha256 => {
+				new_scheme::<Rsa4096PssRestrictedWithSha256>()
+			}
+			SchemeName::Rsa2048PssRestrictedWithSha384 => {
+				new_scheme::<Rsa2048PssRestrictedWithSha384>()
+			}
+			SchemeName::Rsa4096PssRestrictedWithSha384 => {
+				new_scheme::<Rsa4096PssRestrictedWithSha384>()
+			}
+			SchemeName::Rsa2048PssRestrictedWithSha512 => {
+				new_scheme::<Rsa2048PssRestrictedWithSha512>()
+			}
+			SchemeName::Rsa4096PssRestrictedWithSha512 => {
+				new_scheme::<Rsa4096PssRestrictedWithSha512>()
+			}
 		}
 	}
 }
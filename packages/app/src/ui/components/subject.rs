@@ -40,12 +40,12 @@ pub fn Subject(props: &SubjectProps) -> Html {
 			if let Some(onchange) = onchange {
 				let mut parts = Vec::<RelativeDistinguishedName>::default();
 
-				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::CN, &cn);
-				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::O, &o);
-				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::OU, &ou);
-				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::L, &l);
-				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::ST, &s);
-				push_if_not_empty::<PrintableStringRef>(&mut parts, rfc4519::C, &c);
+				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::CN, cn);
+				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::O, o);
+				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::OU, ou);
+				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::L, l);
+				push_if_not_empty::<Utf8StringRef>(&mut parts, rfc4519::ST, s);
+				push_if_not_empty::<PrintableStringRef>(&mut parts, rfc4519::C, c);
 
 				let value = RdnSequence(parts);
 
@@ -90,7 +90,7 @@ fn push_if_not_empty<'a, T: IntoAttributeTypeAndValue<'a>>(
 	oid: ObjectIdentifier,
 	value: &'a str,
 ) {
-	if value == "" {
+	if value.is_empty() {
 		return;
 	};
 
@@ -0,0 +1,178 @@
+use std::str::FromStr;
+
+use enum_iterator::all;
+use num_enum::FromPrimitive;
+use wasm_bindgen::JsCast;
+use web_sys::{HtmlInputElement, HtmlOptionElement, HtmlSelectElement};
+use yew::prelude::*;
+
+use gen::name_constraints::{Subtree, SubtreeType};
+
+use crate::ui::hooks::*;
+
+#[derive(Debug, PartialEq, Properties)]
+pub struct SubtreeListProps {
+	#[prop_or_default]
+	pub slot: Option<Slot<Vec<Subtree>>>,
+	#[prop_or_default]
+	pub title: String,
+}
+
+#[derive(Debug)]
+pub struct SubtreeList {
+	list: Vec<Subtree>,
+	to_add_type: SubtreeType,
+	to_add_value: String,
+	error: Option<String>,
+}
+
+#[derive(Debug)]
+pub enum SubtreeListMessage {
+	UpdateToAddType(SubtreeType),
+	UpdateToAddValue(String),
+	Add,
+	Remove(usize),
+}
+
+impl SubtreeList {
+	fn emit(&self, ctx: &Context<Self>) {
+		if let Some(slot) = ctx.props().slot.as_ref() {
+			slot.set(self.list.clone())
+		}
+	}
+}
+
+impl Component for SubtreeList {
+	type Properties = SubtreeListProps;
+	type Message = SubtreeListMessage;
+
+	fn create(ctx: &Context<Self>) -> Self {
+		Self {
+			list: ctx
+				.props()
+				.slot
+				.clone()
+				.map(|s| s.get())
+				.unwrap_or_default(),
+			to_add_type: SubtreeType::default(),
+			to_add_value: String::default(),
+			error: None,
+		}
+	}
+
+	fn update(&mut self, ctx: &Context<Self>, msg: Self::Message) -> bool {
+		match msg {
+			SubtreeListMessage::UpdateToAddType(value) => {
+				self.to_add_type = value;
+				false
+			}
+			SubtreeListMessage::UpdateToAddValue(value) => {
+				self.to_add_value = value;
+				false
+			}
+			SubtreeListMessage::Add => {
+				match Subtree::parse(self.to_add_type, &self.to_add_value) {
+					Ok(subtree) => {
+						self.list.push(subtree);
+						self.error = None;
+						self.emit(ctx);
+					}
+					Err(err) => self.error = Some(err.to_string()),
+				}
+
+				true
+			}
+			SubtreeListMessage::Remove(index) => {
+				self.list.remove(index);
+				self.emit(ctx);
+
+				true
+			}
+		}
+	}
+
+	fn view(&self, ctx: &Context<Self>) -> Html {
+		let onchange_to_add_type = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlSelectElement>().unwrap();
+			let selected = target
+				.selected_options()
+				.item(0)
+				.unwrap()
+				.dyn_into::<HtmlOptionElement>()
+				.unwrap();
+
+			let value = SubtreeType::from_primitive(FromStr::from_str(&selected.value()).unwrap());
+
+			SubtreeListMessage::UpdateToAddType(value)
+		});
+
+		let onchange_to_add_value = ctx.link().callback(|evt: Event| {
+			let target = evt.target_dyn_into::<HtmlInputElement>().unwrap();
+			let value = target.value();
+
+			SubtreeListMessage::UpdateToAddValue(value)
+		});
+
+		let onclick_add = ctx.link().callback(|_| SubtreeListMessage::Add);
+
+		let onclick_remove = ctx
+			.link()
+			.callback(|index: usize| SubtreeListMessage::Remove(index));
+
+		html! {
+			<div>
+				<span>{ ctx.props().title.clone() }</span>
+				<ul>
+					{
+						for self.list.iter()
+							.enumerate()
+							.map(|(index, subtree)| {
+								let onclick_remove = onclick_remove.clone();
+
+								html!(
+									<SubtreeItem subtree={ subtree.clone() } onremove={ move |_| { onclick_remove.emit(index) } } />
+								)
+							})
+					}
+				</ul>
+				<div>
+					<select onchange={ onchange_to_add_type }>
+						{ for all::<SubtreeType>().map(|st| {
+							html!(
+								<option value={ u8::from(st).to_string() }
+									selected={ self.to_add_type == st }>
+									{ format!("{}", st) }
+								</option>
+							)
+						}) }
+					</select>
+					<input value={ self.to_add_value.clone() } onchange={ onchange_to_add_value } />
+					<button onclick={ onclick_add }>{ "Add" }</button>
+				</div>
+				if let Some(error) = self.error.as_ref() {
+					<p>{ error }</p>
+				}
+			</div>
+		}
+	}
+}
+
+#[derive(Debug, Properties, PartialEq)]
+struct SubtreeItemProps {
+	subtree: Subtree,
+	onremove: Callback<()>,
+}
+
+#[function_component]
+fn SubtreeItem(props: &SubtreeItemProps) -> Html {
+	let SubtreeItemProps { subtree, onremove } = props;
+	let onremove = onremove.clone();
+
+	html!(
+		<li>
+			<p>{ subtree.r#type() }</p>
+			<p>{ subtree.text() }</p>
+			<button onclick={ move |_| { onremove.emit(()) } }>{ "Remove" }</button>
+		</li>
+	)
+}
@@ -50,11 +50,18 @@ impl<T: Clone + PartialEq> PartialEq for Slot<T> {
 }
 
 #[hook]
-pub fn use_slot<T: Clone + 'static>(initial: impl FnOnce() -> T) -> Slot<T> {
+pub fn use_slot<T, F>(initial: F) -> Slot<T>
+where
+	T: Clone + 'static,
+	F: FnOnce() -> T,
+{
 	Slot(use_state(initial))
 }
 
 #[hook]
-pub fn use_slot_with_default<T: Clone + Default + 'static>() -> Slot<T> {
+pub fn use_slot_with_default<T>() -> Slot<T>
+where
+	T: Clone + Default + 'static,
+{
 	Slot(use_state(|| T::default()))
 }
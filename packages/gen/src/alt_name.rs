@@ -1,14 +1,22 @@
 use std::net::IpAddr;
 use std::str::FromStr;
 
-use der::asn1::{Ia5String, OctetString};
+use const_oid::ObjectIdentifier;
+use der::asn1::{Ia5String, OctetString, Utf8StringRef};
+use der::{Any, Tag};
 use enum_iterator::Sequence;
 use num_enum::{FromPrimitive, IntoPrimitive};
 use parse_display::Display;
-use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::name::{GeneralName, OtherName};
+use x509_cert::name::Name;
 
 use common::*;
 
+const MAX_DNS_NAME_LENGTH: usize = 253;
+const MAX_DNS_LABEL_LENGTH: usize = 63;
+
+const ID_MS_UPN: ObjectIdentifier = ObjectIdentifier::new_unwrap("1.3.6.1.4.1.311.20.2.3");
+
 #[derive(
 	Debug, Clone, Copy, PartialEq, Display, Sequence, Default, FromPrimitive, IntoPrimitive,
 )]
@@ -17,26 +25,100 @@ pub enum AltNameType {
 	#[default]
 	DNS,
 	IP,
+	Email,
+	URI,
+	UPN,
+	#[display("Other Name")]
+	OtherName,
+	#[display("Directory Name")]
+	DirectoryName,
+	#[display("Registered ID")]
+	RegisteredID,
 }
 
 #[derive(Debug, Clone, PartialEq)]
 pub enum AltName {
 	DNS(String),
 	IP(IpAddr),
+	Email(String),
+	URI(String),
+	UPN(String),
+	OtherName(ObjectIdentifier, String),
+	DirectoryName(Name),
+	RegisteredID(ObjectIdentifier),
 }
 
+#[derive(Debug, Clone, PartialEq, Display)]
+pub enum AltNameError {
+	#[display("value is empty")]
+	Empty,
+	#[display("invalid IP address {0}")]
+	InvalidIpAddress(String),
+	#[display("invalid DNS name {0}: {1}")]
+	InvalidDnsName(String, &'static str),
+	#[display("invalid internationalized domain name {0}")]
+	InvalidIdn(String),
+	#[display("invalid email address {0}")]
+	InvalidEmail(String),
+	#[display("invalid URI {0}")]
+	InvalidUri(String),
+	#[display("invalid other name {0}, expected OID;value")]
+	InvalidOtherName(String),
+	#[display("invalid object identifier {0}")]
+	InvalidOid(String),
+	#[display("invalid directory name {0}")]
+	InvalidDirectoryName(String),
+}
+
+impl std::error::Error for AltNameError {}
+
 impl AltName {
-	pub fn parse(typ: AltNameType, val: &str) -> Self {
-		match typ {
-			AltNameType::DNS => AltName::DNS(val.to_owned()),
-			AltNameType::IP => AltName::IP(IpAddr::from_str(val).unwrap()),
+	pub fn parse(typ: AltNameType, val: &str) -> Result<Self, AltNameError> {
+		let val = val.trim();
+
+		if val.is_empty() {
+			return Err(AltNameError::Empty);
 		}
+
+		Ok(match typ {
+			AltNameType::DNS => AltName::DNS(parse_dns_name(val, true)?),
+			AltNameType::IP => AltName::IP(
+				IpAddr::from_str(val)
+					.map_err(|_| AltNameError::InvalidIpAddress(val.to_owned()))?,
+			),
+			AltNameType::Email => AltName::Email(parse_email(val)?),
+			AltNameType::URI => AltName::URI(parse_uri(val)?),
+			AltNameType::UPN => match val.split_once('@') {
+				Some((user, domain)) if !user.is_empty() && !domain.is_empty() => {
+					AltName::UPN(val.to_owned())
+				}
+				_ => return Err(AltNameError::InvalidEmail(val.to_owned())),
+			},
+			AltNameType::OtherName => {
+				let (oid, value) = val
+					.split_once(';')
+					.ok_or_else(|| AltNameError::InvalidOtherName(val.to_owned()))?;
+
+				AltName::OtherName(parse_oid(oid.trim())?, value.to_owned())
+			}
+			AltNameType::DirectoryName => AltName::DirectoryName(
+				Name::from_str(val)
+					.map_err(|_| AltNameError::InvalidDirectoryName(val.to_owned()))?,
+			),
+			AltNameType::RegisteredID => AltName::RegisteredID(parse_oid(val)?),
+		})
 	}
 
 	pub fn r#type(&self) -> &'static str {
 		match self {
 			AltName::DNS(_) => "DNS",
 			AltName::IP(_) => "IP",
+			AltName::Email(_) => "Email",
+			AltName::URI(_) => "URI",
+			AltName::UPN(_) => "UPN",
+			AltName::OtherName(_, _) => "Other Name",
+			AltName::DirectoryName(_) => "Directory Name",
+			AltName::RegisteredID(_) => "Registered ID",
 		}
 	}
 
@@ -44,10 +126,107 @@ impl AltName {
 		match self {
 			AltName::DNS(name) => name.clone(),
 			AltName::IP(ip) => ip.to_string(),
+			AltName::Email(email) => email.clone(),
+			AltName::URI(uri) => uri.clone(),
+			AltName::UPN(upn) => upn.clone(),
+			AltName::OtherName(oid, value) => format!("{oid};{value}"),
+			AltName::DirectoryName(name) => name.to_string(),
+			AltName::RegisteredID(oid) => oid.to_string(),
 		}
 	}
 }
 
+fn parse_dns_name(name: &str, allow_wildcard: bool) -> Result<String, AltNameError> {
+	let invalid = |reason| AltNameError::InvalidDnsName(name.to_owned(), reason);
+
+	let (wildcard, domain) = match name.strip_prefix("*.") {
+		Some(domain) if allow_wildcard => (true, domain),
+		Some(_) => return Err(invalid("wildcards are not allowed")),
+		None => (false, name),
+	};
+
+	if domain.contains('*') {
+		return Err(invalid("wildcard must be the entire left-most label"));
+	}
+
+	let domain = if domain.is_ascii() {
+		domain.to_owned()
+	} else {
+		idna::domain_to_ascii(domain).map_err(|_| AltNameError::InvalidIdn(name.to_owned()))?
+	};
+
+	if domain.len() > MAX_DNS_NAME_LENGTH {
+		return Err(invalid("name is longer than 253 characters"));
+	}
+
+	let labels: Vec<&str> = domain.split('.').collect();
+
+	for label in &labels {
+		if label.is_empty() {
+			return Err(invalid("empty label"));
+		}
+
+		if label.len() > MAX_DNS_LABEL_LENGTH {
+			return Err(invalid("label is longer than 63 characters"));
+		}
+
+		if !label.chars().all(|c| c.is_ascii_alphanumeric() || c == '-') {
+			return Err(invalid(
+				"labels may only contain letters, digits and hyphens",
+			));
+		}
+
+		if label.starts_with('-') || label.ends_with('-') {
+			return Err(invalid("labels may not start or end with a hyphen"));
+		}
+	}
+
+	if wildcard && labels.len() < 2 {
+		return Err(invalid("wildcard requires at least two more labels"));
+	}
+
+	Ok(if wildcard {
+		format!("*.{domain}")
+	} else {
+		domain
+	})
+}
+
+fn parse_email(email: &str) -> Result<String, AltNameError> {
+	let invalid = || AltNameError::InvalidEmail(email.to_owned());
+
+	let (local, domain) = email.rsplit_once('@').ok_or_else(invalid)?;
+
+	if local.is_empty() || !local.is_ascii() || local.contains(char::is_whitespace) {
+		return Err(invalid());
+	}
+
+	let domain = parse_dns_name(domain, false).map_err(|_| invalid())?;
+
+	Ok(format!("{local}@{domain}"))
+}
+
+fn parse_uri(uri: &str) -> Result<String, AltNameError> {
+	let invalid = || AltNameError::InvalidUri(uri.to_owned());
+
+	let (scheme, rest) = uri.split_once(':').ok_or_else(invalid)?;
+
+	let valid_scheme = scheme.starts_with(|c: char| c.is_ascii_alphabetic())
+		&& scheme
+			.chars()
+			.all(|c| c.is_ascii_alphanumeric() || matches!(c, '+' | '-' | '.'));
+
+	if !valid_scheme || rest.is_empty() || !uri.is_ascii() || uri.contains(char::is_whitespace) {
+		return Err(invalid());
+	}
+
+	Ok(uri.to_owned())
+}
+
+fn parse_oid(oid: &str) -> Result<ObjectIdentifier, AltNameError> {
+	ObjectIdentifier::new(oid).map_err(|_| AltNameError::InvalidOid(oid.to_owned()))
+}
+
 impl TryFrom<AltName> for GeneralName {
 	type Error = Error;
 
@@ -58,6 +237,184 @@ impl TryFrom<AltName> for GeneralName {
 				IpAddr::V4(ip) => OctetString::new(ip.octets())?,
 				IpAddr::V6(ip) => OctetString::new(ip.octets())?,
 			}),
+			AltName::Email(email) => GeneralName::Rfc822Name(Ia5String::new(&email)?),
+			AltName::URI(uri) => GeneralName::UniformResourceIdentifier(Ia5String::new(&uri)?),
+			AltName::UPN(upn) => GeneralName::OtherName(OtherName {
+				type_id: ID_MS_UPN,
+				value: Any::new(Tag::Utf8String, upn.as_bytes())?,
+			}),
+			AltName::OtherName(oid, value) => GeneralName::OtherName(OtherName {
+				type_id: oid,
+				value: Any::new(Tag::Utf8String, value.as_bytes())?,
+			}),
+			AltName::DirectoryName(name) => GeneralName::DirectoryName(name),
+			AltName::RegisteredID(oid) => GeneralName::RegisteredId(oid),
+		})
+	}
+}
+
+impl TryFrom<GeneralName> for AltName {
+	type Error = Error;
+
+	fn try_from(value: GeneralName) -> Result<AltName, Self::Error> {
+		Ok(match value {
+			GeneralName::DnsName(name) => AltName::DNS(name.to_string()),
+			GeneralName::IpAddress(ip) => AltName::IP(match ip.as_bytes() {
+				&[a, b, c, d] => IpAddr::from([a, b, c, d]),
+				bytes => IpAddr::from(
+					<[u8; 16]>::try_from(bytes)
+						.map_err(|_| Error::msg("invalid IP address length"))?,
+				),
+			}),
+			GeneralName::Rfc822Name(email) => AltName::Email(email.to_string()),
+			GeneralName::UniformResourceIdentifier(uri) => AltName::URI(uri.to_string()),
+			GeneralName::OtherName(other_name) => {
+				let value = Utf8StringRef::try_from(&other_name.value)?.to_string();
+
+				match other_name.type_id {
+					ID_MS_UPN => AltName::UPN(value),
+					oid => AltName::OtherName(oid, value),
+				}
+			}
+			GeneralName::DirectoryName(name) => AltName::DirectoryName(name),
+			GeneralName::RegisteredId(oid) => AltName::RegisteredID(oid),
+			_ => return Err(Error::msg("unsupported alternative name type")),
 		})
 	}
 }
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn malformed_structured_names_are_errors() {
+		assert_eq!(
+			AltName::parse(AltNameType::OtherName, "1.2.3.4"),
+			Err(AltNameError::InvalidOtherName("1.2.3.4".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::OtherName, "not-an-oid;value"),
+			Err(AltNameError::InvalidOid("not-an-oid".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::DirectoryName, "not a name"),
+			Err(AltNameError::InvalidDirectoryName("not a name".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::RegisteredID, "1"),
+			Err(AltNameError::InvalidOid("1".to_owned()))
+		);
+	}
+
+	#[test]
+	fn structured_names_are_parsed() {
+		let oid = ObjectIdentifier::new_unwrap("1.2.3.4");
+
+		assert_eq!(
+			AltName::parse(AltNameType::OtherName, "1.2.3.4;value"),
+			Ok(AltName::OtherName(oid, "value".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::DirectoryName, "CN=example,O=Example"),
+			Ok(AltName::DirectoryName(
+				Name::from_str("CN=example,O=Example").unwrap()
+			))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::RegisteredID, "1.2.3.4"),
+			Ok(AltName::RegisteredID(oid))
+		);
+	}
+
+	#[test]
+	fn dns_names_are_validated() {
+		let invalid = |name: &str, reason| {
+			assert_eq!(
+				AltName::parse(AltNameType::DNS, name),
+				Err(AltNameError::InvalidDnsName(name.to_owned(), reason))
+			);
+		};
+
+		invalid("*.com", "wildcard requires at least two more labels");
+		invalid("a.*.b", "wildcard must be the entire left-most label");
+		invalid(
+			"*a.example.com",
+			"wildcard must be the entire left-most label",
+		);
+		invalid(
+			"-a.example.com",
+			"labels may not start or end with a hyphen",
+		);
+		invalid(
+			"a-.example.com",
+			"labels may not start or end with a hyphen",
+		);
+		invalid("a..example.com", "empty label");
+		invalid(
+			"a_b.example.com",
+			"labels may only contain letters, digits and hyphens",
+		);
+		invalid(
+			&format!("{}.example.com", "a".repeat(64)),
+			"label is longer than 63 characters",
+		);
+
+		let label = "a".repeat(63);
+
+		assert_eq!(
+			AltName::parse(AltNameType::DNS, &format!("{label}.example.com")),
+			Ok(AltName::DNS(format!("{label}.example.com")))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::DNS, "*.example.com"),
+			Ok(AltName::DNS("*.example.com".to_owned()))
+		);
+	}
+
+	#[test]
+	fn unicode_names_are_converted_to_punycode() {
+		assert_eq!(
+			AltName::parse(AltNameType::DNS, "bücher.example"),
+			Ok(AltName::DNS("xn--bcher-kva.example".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::DNS, "*.bücher.example"),
+			Ok(AltName::DNS("*.xn--bcher-kva.example".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::Email, "user@bücher.example"),
+			Ok(AltName::Email("user@xn--bcher-kva.example".to_owned()))
+		);
+	}
+
+	#[test]
+	fn malformed_addresses_are_errors() {
+		assert_eq!(
+			AltName::parse(AltNameType::IP, "256.0.0.1"),
+			Err(AltNameError::InvalidIpAddress("256.0.0.1".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::IP, "::1::"),
+			Err(AltNameError::InvalidIpAddress("::1::".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::Email, "user@*.example.com"),
+			Err(AltNameError::InvalidEmail("user@*.example.com".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::URI, "example.com/path"),
+			Err(AltNameError::InvalidUri("example.com/path".to_owned()))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::URI, "https://example.com/a b"),
+			Err(AltNameError::InvalidUri(
+				"https://example.com/a b".to_owned()
+			))
+		);
+		assert_eq!(
+			AltName::parse(AltNameType::IP, " 10.0.0.1 "),
+			Ok(AltName::IP(IpAddr::from([10, 0, 0, 1])))
+		);
+	}
+}
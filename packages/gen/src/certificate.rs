@@ -1,16 +1,22 @@
 use std::time::Duration;
 
-use const_oid::AssociatedOid;
-use der::asn1::{BitString, GeneralizedTime, OctetString};
-use der::Encode;
+use const_oid::db::rfc5280::{ID_AD_CA_ISSUERS, ID_AD_OCSP};
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use der::asn1::{BitString, GeneralizedTime, Ia5String, OctetString, UtcTime};
+use der::{DateTime, Encode};
 use derive_builder::Builder;
-use time::{OffsetDateTime, PrimitiveDateTime};
-use uuid::Uuid;
-use x509_cert::ext::pkix::name::GeneralName;
-use x509_cert::ext::pkix::{BasicConstraints, KeyUsage, KeyUsages, SubjectAltName};
+use enum_iterator::Sequence;
+use parse_display::Display;
+use spki::SubjectPublicKeyInfoOwned;
+use time::{OffsetDateTime, PrimitiveDateTime, UtcOffset};
+use x509_cert::ext::pkix::crl::dp::DistributionPoint;
+use x509_cert::ext::pkix::name::{DistributionPointName, GeneralName};
+use x509_cert::ext::pkix::{
+	AccessDescription, AuthorityInfoAccessSyntax, AuthorityKeyIdentifier, BasicConstraints,
+	CrlDistributionPoints, ExtendedKeyUsage, KeyUsage, SubjectAltName, SubjectKeyIdentifier,
+};
 use x509_cert::ext::Extension;
 use x509_cert::name::Name;
-use x509_cert::serial_number::SerialNumber;
 use x509_cert::time::{Time, Validity};
 use x509_cert::{Certificate, TbsCertificate, Version};
 
@@ -18,20 +24,104 @@ use crate::scheme::{PrivateKey, SignatureStrategy};
 use common::*;
 
 use super::alt_name::AltName;
+use super::context::Context;
+use super::custom_extension::CustomExtension;
+use super::duration::CalendarDuration;
+use super::key_id::KeyIdMethod;
+use super::key_usage::default_key_usage;
+use super::name_constraints::{to_name_constraints, Subtree};
+use super::policy::{policy_extensions, CaPolicy, Policy};
+use super::serial::SerialStrategy;
+
+const MIN_ENCODABLE_YEAR: i32 = 1970;
+const UTC_TIME_START_YEAR: u16 = 1950;
+const UTC_TIME_END_YEAR: u16 = 2050;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Display, Sequence)]
+pub enum TimeEncoding {
+	#[default]
+	#[display("RFC 5280")]
+	Rfc5280,
+	#[display("UTCTime")]
+	UtcTime,
+	#[display("GeneralizedTime")]
+	GeneralizedTime,
+}
+
+impl TimeEncoding {
+	pub fn validate(&self, date_time: OffsetDateTime) -> Result<()> {
+		let year = date_time.to_offset(UtcOffset::UTC).year();
+
+		if year < MIN_ENCODABLE_YEAR {
+			return Err(Error::msg(format!(
+				"times before {MIN_ENCODABLE_YEAR} cannot be encoded"
+			)));
+		}
+
+		if *self == TimeEncoding::UtcTime
+			&& !(i32::from(UTC_TIME_START_YEAR)..i32::from(UTC_TIME_END_YEAR)).contains(&year)
+		{
+			return Err(Error::msg(format!(
+				"UTCTime can only encode years {UTC_TIME_START_YEAR} to {}, use GeneralizedTime for {year}",
+				UTC_TIME_END_YEAR - 1
+			)));
+		}
+
+		Ok(())
+	}
+}
 
 #[derive(Debug, Builder)]
-#[builder(pattern = "owned")]
+#[builder(pattern = "owned", build_fn(validate = "Self::validate"))]
 pub struct Options {
 	#[builder(setter(into))]
 	issuer: Name,
 	#[builder(setter)]
 	subject: Name,
-	#[builder(setter(into))]
-	duration: Duration,
+	#[builder(default, setter(into))]
+	duration: Option<CalendarDuration>,
+	#[builder(default, setter(into))]
+	not_before: Option<OffsetDateTime>,
+	#[builder(default, setter(into))]
+	not_after: Option<OffsetDateTime>,
+	#[builder(default, setter(into))]
+	backdate: Duration,
+	#[builder(default)]
+	time_encoding: TimeEncoding,
+	#[builder(default, setter(into))]
+	serial: SerialStrategy,
 	#[builder(default = "false", setter(custom))]
 	is_ca: bool,
+	#[builder(default, setter(into))]
+	path_len_constraint: Option<u8>,
 	#[builder(setter(into))]
 	alt_names: Vec<AltName>,
+	#[builder(default, setter(into))]
+	key_usage: Option<KeyUsage>,
+	#[builder(default, setter(into))]
+	key_usage_critical: Option<bool>,
+	#[builder(default, setter(into))]
+	ext_key_usage: Vec<ObjectIdentifier>,
+	#[builder(default, setter(into))]
+	permitted_subtrees: Vec<Subtree>,
+	#[builder(default, setter(into))]
+	excluded_subtrees: Vec<Subtree>,
+	#[builder(default, setter(into))]
+	policies: Vec<Policy>,
+	#[builder(default, setter(into))]
+	ca_policy: Option<CaPolicy>,
+	#[builder(default, setter(into))]
+	ocsp_urls: Vec<String>,
+	#[builder(default, setter(into))]
+	ca_issuers_urls: Vec<String>,
+	#[builder(default, setter(into))]
+	crl_urls: Vec<String>,
+	#[builder(default, setter(into))]
+	custom_extensions: Vec<CustomExtension>,
+	#[builder(default)]
+	key_id_method: KeyIdMethod,
+	#[builder(default, setter(into))]
+	authority_key_id: Option<OctetString>,
 }
 
 impl OptionsBuilder {
@@ -40,27 +130,162 @@ impl OptionsBuilder {
 
 		self
 	}
+
+	fn validate(&self) -> Result<(), String> {
+		if self.duration.flatten().is_none() && self.not_after.flatten().is_none() {
+			return Err("either a duration or a notAfter time is required".to_owned());
+		}
+
+		if self.path_len_constraint.flatten().is_some() && self.is_ca != Some(true) {
+			return Err("path length constraint requires a CA certificate".to_owned());
+		}
+
+		let has_subtrees = |subtrees: &Option<Vec<Subtree>>| {
+			subtrees
+				.as_ref()
+				.is_some_and(|subtrees| !subtrees.is_empty())
+		};
+
+		if (has_subtrees(&self.permitted_subtrees) || has_subtrees(&self.excluded_subtrees))
+			&& self.is_ca != Some(true)
+		{
+			return Err("name constraints require a CA certificate".to_owned());
+		}
+
+		if self.ca_policy.as_ref().is_some_and(Option::is_some) && self.is_ca != Some(true) {
+			return Err("policy mappings and constraints require a CA certificate".to_owned());
+		}
+
+		Ok(())
+	}
 }
 
 pub fn gen_tbs_certificate(
 	strategy: &dyn SignatureStrategy,
-	key: &dyn PrivateKey,
+	subject_public_key_info: SubjectPublicKeyInfoOwned,
 	options: Options,
+	context: &mut Context,
 ) -> Result<TbsCertificate> {
-	let now = OffsetDateTime::now_utc();
-	let expiry = now.clone() + options.duration;
+	let start = options.not_before.unwrap_or_else(|| context.now());
 
-	let not_before = Time::GeneralTime(GeneralizedTime::from_date_time(
-		PrimitiveDateTime::new(now.date(), now.time()).try_into()?,
-	));
-	let not_after = Time::GeneralTime(GeneralizedTime::from_date_time(
-		PrimitiveDateTime::new(expiry.date(), expiry.time()).try_into()?,
-	));
+	let not_before = start - options.backdate;
+	let not_after = match (options.not_after, options.duration) {
+		(Some(not_after), _) => not_after,
+		(None, Some(duration)) => duration.add_to(start)?,
+		(None, None) => return Err(Error::msg("missing certificate validity end")),
+	};
+
+	if not_after <= not_before {
+		return Err(Error::msg("notAfter must be later than notBefore"));
+	}
+
+	let mut extensions = vec![
+		BasicConstraints {
+			ca: options.is_ca,
+			path_len_constraint: options.path_len_constraint,
+		}
+		.to_extension(true)?,
+		options
+			.key_usage
+			.unwrap_or_else(|| default_key_usage(&subject_public_key_info, options.is_ca))
+			.to_extension(options.key_usage_critical.unwrap_or(options.is_ca))?,
+	];
+
+	if !options.ext_key_usage.is_empty() {
+		extensions.push(ExtendedKeyUsage(options.ext_key_usage).to_extension(false)?);
+	}
+
+	if !options.permitted_subtrees.is_empty() || !options.excluded_subtrees.is_empty() {
+		extensions.push(
+			to_name_constraints(options.permitted_subtrees, options.excluded_subtrees)?
+				.to_extension(true)?,
+		);
+	}
+
+	let subject_key_id = options.key_id_method.key_id(&subject_public_key_info)?;
+
+	extensions.push(SubjectKeyIdentifier(subject_key_id.clone()).to_extension(false)?);
+	extensions.push(
+		AuthorityKeyIdentifier {
+			key_identifier: Some(options.authority_key_id.unwrap_or(subject_key_id)),
+			authority_cert_issuer: None,
+			authority_cert_serial_number: None,
+		}
+		.to_extension(false)?,
+	);
+
+	extensions.extend(policy_extensions(options.policies, options.ca_policy)?);
+
+	let access_descriptions = options
+		.ocsp_urls
+		.iter()
+		.map(|url| (ID_AD_OCSP, url))
+		.chain(
+			options
+				.ca_issuers_urls
+				.iter()
+				.map(|url| (ID_AD_CA_ISSUERS, url)),
+		)
+		.map(|(access_method, url)| {
+			Ok(AccessDescription {
+				access_method,
+				access_location: uri(url)?,
+			})
+		})
+		.collect::<Result<Vec<_>>>()?;
+
+	if !access_descriptions.is_empty() {
+		extensions.push(AuthorityInfoAccessSyntax(access_descriptions).to_extension(false)?);
+	}
+
+	if !options.crl_urls.is_empty() {
+		extensions.push(
+			CrlDistributionPoints(
+				options
+					.crl_urls
+					.iter()
+					.map(|url| {
+						Ok(DistributionPoint {
+							distribution_point: Some(DistributionPointName::FullName(vec![uri(
+								url,
+							)?])),
+							reasons: None,
+							crl_issuer: None,
+						})
+					})
+					.collect::<Result<_>>()?,
+			)
+			.to_extension(false)?,
+		);
+	}
+
+	extensions.push(
+		SubjectAltName::from(
+			options
+				.alt_names
+				.into_iter()
+				.map(TryFrom::try_from)
+				.collect::<Result<Vec<GeneralName>>>()?,
+		)
+		.to_extension(true)?,
+	);
+
+	for custom_extension in options.custom_extensions {
+		let extension = Extension::try_from(custom_extension)?;
+
+		match extensions
+			.iter_mut()
+			.find(|existing| existing.extn_id == extension.extn_id)
+		{
+			Some(existing) => *existing = extension,
+			None => extensions.push(extension),
+		}
+	}
 
 	let tbs_certificate = TbsCertificate {
 		version: Version::V3,
 
-		serial_number: SerialNumber::new(Uuid::new_v4().as_bytes())?,
+		serial_number: options.serial.generate(context)?,
 		signature: strategy.signature_algorithm_identifier()?,
 
 		issuer: options.issuer,
@@ -68,34 +293,14 @@ pub fn gen_tbs_certificate(
 
 		subject: options.subject,
 		subject_unique_id: None,
-		subject_public_key_info: key.to_subject_public_key_info()?,
+		subject_public_key_info,
 
 		validity: Validity {
-			not_before,
-			not_after,
+			not_before: to_time(not_before, options.time_encoding)?,
+			not_after: to_time(not_after, options.time_encoding)?,
 		},
 
-		extensions: Some(vec![
-			BasicConstraints {
-				ca: options.is_ca,
-				path_len_constraint: None,
-			}
-			.to_extension(true)?,
-			KeyUsage::from(if options.is_ca {
-				KeyUsages::DigitalSignature | KeyUsages::KeyCertSign
-			} else {
-				KeyUsages::DigitalSignature | KeyUsages::KeyEncipherment
-			})
-			.to_extension(options.is_ca)?,
-			SubjectAltName::from(
-				options
-					.alt_names
-					.into_iter()
-					.map(TryFrom::try_from)
-					.collect::<Result<Vec<GeneralName>>>()?,
-			)
-			.to_extension(true)?,
-		]),
+		extensions: Some(extensions),
 	};
 
 	Ok(tbs_certificate)
@@ -105,8 +310,9 @@ pub fn sign_certificate(
 	strategy: &dyn SignatureStrategy,
 	key: &dyn PrivateKey,
 	tbs_certificate: TbsCertificate,
+	context: &mut Context,
 ) -> Result<Certificate> {
-	let signature = key.sign(&tbs_certificate.to_der()?)?;
+	let signature = key.sign_with_rng(&tbs_certificate.to_der()?, context.rng())?;
 
 	let certificate = Certificate {
 		tbs_certificate,
@@ -117,7 +323,36 @@ pub fn sign_certificate(
 	Ok(certificate)
 }
 
-trait ToExtension: AssociatedOid + Encode {
+fn uri(url: &str) -> Result<GeneralName> {
+	Ok(GeneralName::UniformResourceIdentifier(Ia5String::new(url)?))
+}
+
+fn to_time(date_time: OffsetDateTime, encoding: TimeEncoding) -> Result<Time> {
+	encoding.validate(date_time)?;
+
+	let date_time = date_time.to_offset(UtcOffset::UTC).replace_nanosecond(0)?;
+
+	let date_time: DateTime =
+		PrimitiveDateTime::new(date_time.date(), date_time.time()).try_into()?;
+
+	Ok(match encoding {
+		TimeEncoding::Rfc5280
+			if (UTC_TIME_START_YEAR..UTC_TIME_END_YEAR).contains(&date_time.year()) =>
+		{
+			Time::UtcTime(UtcTime::from_date_time(date_time)?)
+		}
+		TimeEncoding::UtcTime => Time::UtcTime(UtcTime::from_date_time(date_time)?),
+		TimeEncoding::Rfc5280 | TimeEncoding::GeneralizedTime => {
+			Time::GeneralTime(GeneralizedTime::from_date_time(date_time))
+		}
+	})
+}
+
+pub(crate) fn to_offset_date_time(time: Time) -> Result<OffsetDateTime> {
+	Ok(PrimitiveDateTime::try_from(time.to_date_time())?.assume_utc())
+}
+
+pub(crate) trait ToExtension: AssociatedOid + Encode {
 	fn to_extension(&self, critical: bool) -> Result<Extension> {
 		Ok(Extension {
 			extn_id: Self::OID,
@@ -128,3 +363,49 @@ trait ToExtension: AssociatedOid + Encode {
 }
 
 impl<T: AssociatedOid + Encode> ToExtension for T {}
+
+#[cfg(test)]
+mod tests {
+	use time::{Date, Month, Time as TimeOfDay};
+
+	use super::*;
+
+	fn start_of_year(year: i32) -> OffsetDateTime {
+		Date::from_calendar_date(year, Month::January, 1)
+			.unwrap()
+			.with_time(TimeOfDay::MIDNIGHT)
+			.assume_utc()
+	}
+
+	#[test]
+	fn rfc5280_encoding_switches_to_generalized_time_in_2050() {
+		for (year, utc_time) in [(1970, true), (2049, true), (2050, false)] {
+			let time = to_time(start_of_year(year), TimeEncoding::Rfc5280).unwrap();
+
+			assert_eq!(matches!(time, Time::UtcTime(_)), utc_time, "{year}");
+			assert_eq!(to_offset_date_time(time).unwrap(), start_of_year(year));
+		}
+	}
+
+	#[test]
+	fn times_before_1970_are_rejected() {
+		for year in [1949, 1950] {
+			for encoding in enum_iterator::all::<TimeEncoding>() {
+				let err = to_time(start_of_year(year), encoding).unwrap_err();
+
+				assert_eq!(err.to_string(), "times before 1970 cannot be encoded");
+			}
+		}
+	}
+
+	#[test]
+	fn utc_time_override_rejects_2050() {
+		assert_eq!(
+			to_time(start_of_year(2050), TimeEncoding::UtcTime)
+				.unwrap_err()
+				.to_string(),
+			"UTCTime can only encode years 1950 to 2049, use GeneralizedTime for 2050"
+		);
+		assert!(to_time(start_of_year(2050), TimeEncoding::GeneralizedTime).is_ok());
+	}
+}
@@ -0,0 +1,52 @@
+use rand_core::CryptoRngCore;
+use time::OffsetDateTime;
+
+pub trait Clock {
+	fn now(&self) -> OffsetDateTime;
+}
+
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+	fn now(&self) -> OffsetDateTime {
+		OffsetDateTime::now_utc()
+	}
+}
+
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub OffsetDateTime);
+
+impl Clock for FixedClock {
+	fn now(&self) -> OffsetDateTime {
+		self.0
+	}
+}
+
+pub struct Context {
+	rng: Box<dyn CryptoRngCore>,
+	clock: Box<dyn Clock>,
+}
+
+impl Context {
+	pub fn new(rng: impl CryptoRngCore + 'static, clock: impl Clock + 'static) -> Self {
+		Context {
+			rng: Box::new(rng),
+			clock: Box::new(clock),
+		}
+	}
+
+	pub fn rng(&mut self) -> &mut dyn CryptoRngCore {
+		self.rng.as_mut()
+	}
+
+	pub fn now(&self) -> OffsetDateTime {
+		self.clock.now()
+	}
+}
+
+impl Default for Context {
+	fn default() -> Self {
+		Context::new(rand::thread_rng(), SystemClock)
+	}
+}
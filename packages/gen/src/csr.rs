@@ -0,0 +1,77 @@
+use der::asn1::BitString;
+use der::{Encode, EncodePem};
+use pkcs8::LineEnding;
+use x509_cert::attr::Attribute;
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::ext::pkix::SubjectAltName;
+use x509_cert::name::Name;
+use x509_cert::request::{CertReq, CertReqInfo, ExtensionReq, Version};
+
+use common::*;
+
+use super::alt_name::AltName;
+use super::certificate::ToExtension;
+use super::context::Context;
+use super::scheme::{PrivateKey, SignatureStrategy};
+
+#[derive(Debug, Clone)]
+pub struct CsrOptions {
+	pub subject: Name,
+	pub san: Vec<AltName>,
+}
+
+pub fn gen_csr_info(key: &dyn PrivateKey, options: CsrOptions) -> Result<CertReqInfo> {
+	let mut attributes = Vec::new();
+
+	if !options.san.is_empty() {
+		let extension_req = ExtensionReq(vec![SubjectAltName::from(
+			options
+				.san
+				.into_iter()
+				.map(TryFrom::try_from)
+				.collect::<Result<Vec<GeneralName>>>()?,
+		)
+		.to_extension(false)?]);
+
+		attributes.push(Attribute::try_from(extension_req)?);
+	}
+
+	Ok(CertReqInfo {
+		version: Version::V1,
+		subject: options.subject,
+		public_key: key.to_subject_public_key_info()?,
+		attributes: attributes.try_into()?,
+	})
+}
+
+pub fn sign_csr(
+	strategy: &dyn SignatureStrategy,
+	key: &dyn PrivateKey,
+	info: CertReqInfo,
+	context: &mut Context,
+) -> Result<CertReq> {
+	let signature = key.sign_with_rng(&info.to_der()?, context.rng())?;
+
+	Ok(CertReq {
+		info,
+		algorithm: strategy.signature_algorithm_identifier()?,
+		signature: BitString::from_bytes(&signature)?,
+	})
+}
+
+pub fn gen_csr(strategy: &dyn SignatureStrategy, options: CsrOptions) -> Result<(String, String)> {
+	gen_csr_with_context(strategy, options, &mut Context::default())
+}
+
+pub fn gen_csr_with_context(
+	strategy: &dyn SignatureStrategy,
+	options: CsrOptions,
+	context: &mut Context,
+) -> Result<(String, String)> {
+	let key = strategy.generate_key_with_rng(context.rng())?;
+
+	let info = gen_csr_info(key.as_ref(), options)?;
+	let csr = sign_csr(strategy, key.as_ref(), info, context)?;
+
+	Ok((key.to_pem(LineEnding::LF)?, csr.to_pem(LineEnding::LF)?))
+}
@@ -0,0 +1,252 @@
+use base64ct::{Base64, Encoding};
+use const_oid::ObjectIdentifier;
+use der::asn1::{BitString, Ia5String, OctetString, PrintableString};
+use der::{Any, Encode, Tag};
+use enum_iterator::Sequence;
+use nom::IResult;
+use parse_display::Display;
+use x509_cert::ext::Extension;
+
+use common::*;
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, Sequence, Default)]
+pub enum ExtensionValueFormat {
+	#[default]
+	Hex,
+	Base64,
+	#[display("ASN.1")]
+	Asn1,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CustomExtension {
+	pub oid: ObjectIdentifier,
+	pub critical: bool,
+	pub value: Vec<u8>,
+}
+
+impl CustomExtension {
+	pub fn parse(
+		oid: &str,
+		critical: bool,
+		format: ExtensionValueFormat,
+		value: &str,
+	) -> Result<Self> {
+		let value = match format {
+			ExtensionValueFormat::Hex => parse_hex(value)?,
+			ExtensionValueFormat::Base64 => Base64::decode_vec(
+				&value
+					.chars()
+					.filter(|c| !c.is_whitespace())
+					.collect::<String>(),
+			)
+			.map_err(|_| Error::msg("invalid base64 value"))?,
+			ExtensionValueFormat::Asn1 => parse_asn1_str(value)?,
+		};
+
+		Ok(CustomExtension {
+			oid: ObjectIdentifier::new(oid.trim())?,
+			critical,
+			value,
+		})
+	}
+}
+
+impl TryFrom<CustomExtension> for Extension {
+	type Error = Error;
+
+	fn try_from(value: CustomExtension) -> Result<Extension, Self::Error> {
+		Ok(Extension {
+			extn_id: value.oid,
+			critical: value.critical,
+			extn_value: OctetString::new(value.value)?,
+		})
+	}
+}
+
+pub(crate) fn parse_hex(input: &str) -> Result<Vec<u8>> {
+	let digits: Vec<char> = input
+		.chars()
+		.filter(|c| !c.is_whitespace() && *c != ':')
+		.collect();
+
+	if digits.len() % 2 != 0 {
+		return Err(Error::msg("invalid hex value"));
+	}
+
+	digits
+		.chunks(2)
+		.map(|pair| {
+			u8::from_str_radix(&pair.iter().collect::<String>(), 16)
+				.map_err(|_| Error::msg("invalid hex value"))
+		})
+		.collect()
+}
+
+fn parse_asn1_str(input: &str) -> Result<Vec<u8>> {
+	match parse_asn1(input.trim()) {
+		Ok(("", value)) => value,
+		_ => Err(Error::msg("invalid ASN.1 value description")),
+	}
+}
+
+fn parse_asn1(input: &str) -> IResult<&str, Result<Vec<u8>>> {
+	let (rest, value) = nom::branch::alt((
+		parse_constructed,
+		parse_primitive,
+		nom::combinator::map(nom::bytes::complete::tag("NULL"), |_| encode(&Any::null())),
+	))(input)?;
+
+	let (rest, _) = nom::character::complete::space0(rest)?;
+
+	Ok((rest, value))
+}
+
+fn parse_constructed(input: &str) -> IResult<&str, Result<Vec<u8>>> {
+	let (input, tag) = nom::branch::alt((
+		nom::combinator::value(Tag::Sequence, nom::bytes::complete::tag("SEQ")),
+		nom::combinator::value(Tag::Set, nom::bytes::complete::tag("SET")),
+	))(input)?;
+	let (input, _) = nom::character::complete::char('(')(input)?;
+	let (input, _) = nom::character::complete::space0(input)?;
+	let (input, items) = nom::multi::separated_list0(
+		nom::sequence::pair(
+			nom::character::complete::char(','),
+			nom::character::complete::space0,
+		),
+		parse_asn1,
+	)(input)?;
+	let (input, _) = nom::character::complete::char(')')(input)?;
+
+	let value = items
+		.into_iter()
+		.collect::<Result<Vec<_>>>()
+		.and_then(|mut items| {
+			if tag == Tag::Set {
+				items.sort();
+			}
+
+			encode(&Any::new(tag, items.concat())?)
+		});
+
+	Ok((input, value))
+}
+
+fn parse_primitive(input: &str) -> IResult<&str, Result<Vec<u8>>> {
+	let (input, typ) = nom::character::complete::alphanumeric1(input)?;
+	let (input, _) = nom::character::complete::char(':')(input)?;
+	let (input, text) = nom::bytes::complete::take_till(|c| c == ',' || c == ')')(input)?;
+
+	let text = text.trim_end();
+
+	let value = match typ {
+		"BOOL" => match text {
+			"true" => encode(&true),
+			"false" => encode(&false),
+			_ => Err(Error::msg("invalid boolean")),
+		},
+		"INT" => text
+			.parse::<i64>()
+			.map_err(Error::from)
+			.and_then(|int| encode(&int)),
+		"OID" => ObjectIdentifier::new(text)
+			.map_err(Error::from)
+			.and_then(|oid| encode(&oid)),
+		"UTF8" => Any::new(Tag::Utf8String, text.as_bytes())
+			.map_err(Error::from)
+			.and_then(|any| encode(&any)),
+		"IA5" => Ia5String::new(text)
+			.map_err(Error::from)
+			.and_then(|string| encode(&string)),
+		"PRINTABLE" => PrintableString::new(text)
+			.map_err(Error::from)
+			.and_then(|string| encode(&string)),
+		"OCTET" => parse_hex(text).and_then(|bytes| encode(&OctetString::new(bytes)?)),
+		"BITSTRING" => parse_hex(text).and_then(|bytes| encode(&BitString::from_bytes(&bytes)?)),
+		"DER" => parse_hex(text),
+		_ => {
+			return Err(nom::Err::Error(nom::error::Error::new(
+				input,
+				nom::error::ErrorKind::Tag,
+			)))
+		}
+	};
+
+	Ok((input, value))
+}
+
+fn encode(value: &impl Encode) -> Result<Vec<u8>> {
+	Ok(value.to_der()?)
+}
+
+#[cfg(test)]
+mod tests {
+	use der::Decode;
+
+	use super::*;
+
+	fn assert_encodes(description: &str, expected: &str) {
+		let value = parse_asn1_str(description).unwrap();
+
+		assert_eq!(value, parse_hex(expected).unwrap(), "{description}");
+		assert_eq!(Any::from_der(&value).unwrap().to_der().unwrap(), value);
+	}
+
+	#[test]
+	fn primitives_are_encoded() {
+		assert_encodes("BOOL:true", "01 01 FF");
+		assert_encodes("BOOL:false", "01 01 00");
+		assert_encodes("INT:0", "02 01 00");
+		assert_encodes("INT:128", "02 02 00 80");
+		assert_encodes("INT:-129", "02 02 FF 7F");
+		assert_encodes("OID:1.2.840.113549", "06 06 2A 86 48 86 F7 0D");
+		assert_encodes("UTF8:héllo", "0C 06 68 C3 A9 6C 6C 6F");
+		assert_encodes("IA5:abc", "16 03 61 62 63");
+		assert_encodes("PRINTABLE:Ab 1", "13 04 41 62 20 31");
+		assert_encodes("OCTET:01:02", "04 02 01 02");
+		assert_encodes("BITSTRING:FF00", "03 03 00 FF 00");
+		assert_encodes("DER:0500", "05 00");
+		assert_encodes("NULL", "05 00");
+	}
+
+	#[test]
+	fn constructed_values_are_encoded() {
+		assert_encodes("SEQ()", "30 00");
+		assert_encodes(
+			"SEQ(INT:1, SEQ(BOOL:false), NULL)",
+			"30 0A 02 01 01 30 03 01 01 00 05 00",
+		);
+		assert_encodes("SEQ( INT:1 , NULL )", "30 05 02 01 01 05 00");
+		assert_encodes("SET(INT:2, INT:1)", "31 06 02 01 01 02 01 02");
+		assert_encodes("SET(IA5:a, UTF8:b)", "31 06 0C 01 62 16 01 61");
+		assert_encodes(
+			"SEQ(SET(INT:2, INT:1), OID:2.5.29.19)",
+			"30 0D 31 06 02 01 01 02 01 02 06 03 55 1D 13",
+		);
+	}
+
+	#[test]
+	fn malformed_descriptions_are_rejected() {
+		for description in [
+			"",
+			"INT",
+			"INT:abc",
+			"INT:99999999999999999999",
+			"BOOL:yes",
+			"OID:1",
+			"IA5:é",
+			"PRINTABLE:a@b",
+			"OCTET:ABC",
+			"BITSTRING:XY",
+			"FOO:1",
+			"SEQ(INT:1",
+			"SEQ INT:1)",
+			"SEQ(INT:1,)",
+			"SEQ(INT:1) NULL",
+			"SEQ(INT:1))",
+			"SEQ(INT:abc)",
+		] {
+			assert!(parse_asn1_str(description).is_err(), "{description}");
+		}
+	}
+}
@@ -1,60 +1,369 @@
 use std::time::Duration;
 
 use nom::IResult;
+use time::{Date, Month, OffsetDateTime};
 
 use common::*;
 
 const SECS_PER_YEAR: u64 = 365 * SECS_PER_DAY;
 const SECS_PER_MONTH: u64 = 30 * SECS_PER_DAY;
-const SECS_PER_DAY: u64 = 24 * 60 * 60;
+const SECS_PER_WEEK: u64 = 7 * SECS_PER_DAY;
+const SECS_PER_DAY: u64 = 24 * SECS_PER_HOUR;
+const SECS_PER_HOUR: u64 = 60 * SECS_PER_MINUTE;
+const SECS_PER_MINUTE: u64 = 60;
 
-pub fn parse_duration_str(input: &str) -> Result<Duration> {
-	parse_duration(input)
-		.map(|(_, dur)| dur)
-		.map_err(|_| Error::msg("invalid duration"))
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CalendarDuration {
+	pub years: u64,
+	pub months: u64,
+	pub seconds: u64,
 }
 
-fn parse_duration(input: &str) -> IResult<&str, Duration> {
-	let (input, years) = nom::combinator::opt(parse_years)(input)?;
-	let (input, months) = nom::combinator::opt(parse_months)(input)?;
-	let (input, days) = nom::combinator::opt(parse_days)(input)?;
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Unit {
+	Years,
+	Months,
+	Weeks,
+	Days,
+	Hours,
+	Minutes,
+	Seconds,
+}
 
-	nom::combinator::eof(input)?;
+impl CalendarDuration {
+	pub fn add_to(&self, start: OffsetDateTime) -> Result<OffsetDateTime> {
+		let total_months = (start.month() as u64 - 1)
+			.saturating_add(self.months)
+			.saturating_add(self.years.saturating_mul(12));
 
-	let mut secs = 0;
+		let year = i32::try_from(start.year() as i64 + i64::try_from(total_months / 12)?)?;
+		let month = Month::try_from((total_months % 12 + 1) as u8)?;
+		let day = start.day().min(time::util::days_in_year_month(year, month));
 
-	if let Some(years) = years {
-		secs += years * SECS_PER_YEAR;
+		let date_time = start.replace_date(Date::from_calendar_date(year, month, day)?);
+
+		date_time
+			.checked_add(time::Duration::seconds(i64::try_from(self.seconds)?))
+			.ok_or_else(|| Error::msg("duration is out of range"))
 	}
 
-	if let Some(months) = months {
-		secs += months * SECS_PER_MONTH;
+	pub fn to_std_duration(&self) -> Duration {
+		Duration::from_secs(
+			self.years
+				.saturating_mul(SECS_PER_YEAR)
+				.saturating_add(self.months.saturating_mul(SECS_PER_MONTH))
+				.saturating_add(self.seconds),
+		)
 	}
 
-	if let Some(days) = days {
-		secs += days * SECS_PER_DAY;
+	fn add(mut self, amount: u64, unit: Unit) -> Self {
+		let seconds = |secs_per_unit: u64| {
+			self.seconds
+				.saturating_add(amount.saturating_mul(secs_per_unit))
+		};
+
+		match unit {
+			Unit::Years => self.years = self.years.saturating_add(amount),
+			Unit::Months => self.months = self.months.saturating_add(amount),
+			Unit::Weeks => self.seconds = seconds(SECS_PER_WEEK),
+			Unit::Days => self.seconds = seconds(SECS_PER_DAY),
+			Unit::Hours => self.seconds = seconds(SECS_PER_HOUR),
+			Unit::Minutes => self.seconds = seconds(SECS_PER_MINUTE),
+			Unit::Seconds => self.seconds = seconds(1),
+		}
+
+		self
 	}
+}
+
+impl From<Duration> for CalendarDuration {
+	fn from(value: Duration) -> Self {
+		CalendarDuration {
+			years: 0,
+			months: 0,
+			seconds: value.as_secs(),
+		}
+	}
+}
+
+pub fn parse_duration_str(input: &str) -> Result<CalendarDuration> {
+	let result = if input.trim_start().starts_with(['P', 'p']) {
+		parse_iso_duration(input)
+	} else {
+		parse_duration(input)
+	};
+
+	result.map(|(_, dur)| dur).map_err(|err| {
+		let rest = match err {
+			nom::Err::Error(err) | nom::Err::Failure(err) => err.input,
+			nom::Err::Incomplete(_) => "",
+		};
+
+		let position = input[..input.len() - rest.len()].chars().count() + 1;
+
+		match rest.chars().next() {
+			Some(c) => Error::msg(format!(
+				"invalid duration: unexpected '{c}' at position {position}"
+			)),
+			None => Error::msg(format!(
+				"invalid duration: unexpected end of input at position {position}"
+			)),
+		}
+	})
+}
+
+fn parse_duration(input: &str) -> IResult<&str, CalendarDuration> {
+	let (input, _) = nom::character::complete::multispace0(input)?;
+	let (input, components) = nom::multi::many1(parse_component)(input)?;
+
+	nom::combinator::eof(input)?;
+
+	let duration = components
+		.into_iter()
+		.fold(CalendarDuration::default(), |duration, (amount, unit)| {
+			duration.add(amount, unit)
+		});
+
+	Ok((input, duration))
+}
+
+fn parse_component(input: &str) -> IResult<&str, (u64, Unit)> {
+	let (input, amount) = nom::character::complete::u64(input)?;
+	let (input, _) = nom::character::complete::multispace0(input)?;
+	let (input, unit) = nom::combinator::cut(parse_unit)(input)?;
+	let (input, _) = nom::character::complete::multispace0(input)?;
+
+	Ok((input, (amount, unit)))
+}
+
+fn parse_unit(input: &str) -> IResult<&str, Unit> {
+	let (rest, name) = nom::character::complete::alpha1(input)?;
+
+	let unit = match name.to_ascii_lowercase().as_str() {
+		"y" | "yr" | "yrs" | "year" | "years" => Unit::Years,
+		"m" | "mo" | "month" | "months" => Unit::Months,
+		"w" | "week" | "weeks" => Unit::Weeks,
+		"d" | "day" | "days" => Unit::Days,
+		"h" | "hr" | "hrs" | "hour" | "hours" => Unit::Hours,
+		"min" | "mins" | "minute" | "minutes" => Unit::Minutes,
+		"s" | "sec" | "secs" | "second" | "seconds" => Unit::Seconds,
+		_ => {
+			return Err(nom::Err::Failure(nom::error::Error::new(
+				input,
+				nom::error::ErrorKind::Tag,
+			)))
+		}
+	};
 
-	Ok((input, Duration::new(secs, 0)))
+	Ok((rest, unit))
 }
 
-fn parse_years(input: &str) -> IResult<&str, u64> {
-	let (input, years) = nom::character::complete::u64(input)?;
-	let (input, _) = nom::character::complete::char('y')(input)?;
+fn parse_iso_duration(input: &str) -> IResult<&str, CalendarDuration> {
+	let (input, _) = nom::character::complete::multispace0(input)?;
+	let (input, _) = nom::character::complete::one_of("Pp")(input)?;
+	let (input, date) = nom::multi::many0(|input| {
+		parse_iso_component(
+			input,
+			&[
+				('Y', Unit::Years),
+				('M', Unit::Months),
+				('W', Unit::Weeks),
+				('D', Unit::Days),
+			],
+		)
+	})(input)?;
+	let (input, time) = nom::combinator::opt(|input| {
+		let (input, _) = nom::character::complete::one_of("Tt")(input)?;
 
-	Ok((input, years))
+		nom::multi::many1(|input| {
+			parse_iso_component(
+				input,
+				&[
+					('H', Unit::Hours),
+					('M', Unit::Minutes),
+					('S', Unit::Seconds),
+				],
+			)
+		})(input)
+	})(input)?;
+	let (input, _) = nom::character::complete::multispace0(input)?;
+
+	nom::combinator::eof(input)?;
+
+	if date.is_empty() && time.is_none() {
+		return Err(nom::Err::Error(nom::error::Error::new(
+			input,
+			nom::error::ErrorKind::Many1,
+		)));
+	}
+
+	let duration = date
+		.into_iter()
+		.chain(time.into_iter().flatten())
+		.fold(CalendarDuration::default(), |duration, (amount, unit)| {
+			duration.add(amount, unit)
+		});
+
+	Ok((input, duration))
 }
 
-fn parse_months(input: &str) -> IResult<&str, u64> {
-	let (input, months) = nom::character::complete::u64(input)?;
-	let (input, _) = nom::character::complete::char('m')(input)?;
+fn parse_iso_component<'a>(
+	input: &'a str,
+	units: &[(char, Unit)],
+) -> IResult<&'a str, (u64, Unit)> {
+	let (rest, amount) = nom::character::complete::u64(input)?;
+	let (rest, designator) = nom::character::complete::anychar(rest)?;
 
-	Ok((input, months))
+	match units
+		.iter()
+		.find(|(unit, _)| unit.eq_ignore_ascii_case(&designator))
+	{
+		Some((_, unit)) => Ok((rest, (amount, *unit))),
+		None => Err(nom::Err::Failure(nom::error::Error::new(
+			&input[input.len() - rest.len() - designator.len_utf8()..],
+			nom::error::ErrorKind::OneOf,
+		))),
+	}
 }
 
-fn parse_days(input: &str) -> IResult<&str, u64> {
-	let (input, days) = nom::character::complete::u64(input)?;
-	let (input, _) = nom::character::complete::char('d')(input)?;
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	fn duration(years: u64, months: u64, seconds: u64) -> CalendarDuration {
+		CalendarDuration {
+			years,
+			months,
+			seconds,
+		}
+	}
+
+	fn date(year: i32, month: Month, day: u8, hour: u8) -> OffsetDateTime {
+		Date::from_calendar_date(year, month, day)
+			.unwrap()
+			.with_hms(hour, 0, 0)
+			.unwrap()
+			.assume_utc()
+	}
+
+	fn error(input: &str) -> String {
+		parse_duration_str(input).unwrap_err().to_string()
+	}
 
-	Ok((input, days))
+	#[test]
+	fn units_are_parsed() {
+		assert_eq!(parse_duration_str("1y").unwrap(), duration(1, 0, 0));
+		assert_eq!(parse_duration_str("2 Years").unwrap(), duration(2, 0, 0));
+		assert_eq!(parse_duration_str("3m").unwrap(), duration(0, 3, 0));
+		assert_eq!(parse_duration_str("3mo").unwrap(), duration(0, 3, 0));
+		assert_eq!(
+			parse_duration_str("1w 2d").unwrap(),
+			duration(0, 0, 9 * SECS_PER_DAY)
+		);
+		assert_eq!(
+			parse_duration_str("1h30min15s").unwrap(),
+			duration(0, 0, SECS_PER_HOUR + 30 * SECS_PER_MINUTE + 15)
+		);
+		assert_eq!(
+			parse_duration_str(" 1 year 6 months 1 day ").unwrap(),
+			duration(1, 6, SECS_PER_DAY)
+		);
+	}
+
+	#[test]
+	fn iso_8601_durations_are_parsed() {
+		assert_eq!(
+			parse_duration_str("P1Y2M3DT4H5M6S").unwrap(),
+			duration(
+				1,
+				2,
+				3 * SECS_PER_DAY + 4 * SECS_PER_HOUR + 5 * SECS_PER_MINUTE + 6
+			)
+		);
+		assert_eq!(
+			parse_duration_str("P2W").unwrap(),
+			duration(0, 0, 14 * SECS_PER_DAY)
+		);
+		assert_eq!(parse_duration_str("PT1M").unwrap(), duration(0, 0, 60));
+		assert_eq!(parse_duration_str("p1m").unwrap(), duration(0, 1, 0));
+	}
+
+	#[test]
+	fn errors_report_the_position() {
+		assert_eq!(
+			error("1d x"),
+			"invalid duration: unexpected 'x' at position 4"
+		);
+		assert_eq!(
+			error("1x"),
+			"invalid duration: unexpected 'x' at position 2"
+		);
+		assert_eq!(
+			error("5"),
+			"invalid duration: unexpected end of input at position 2"
+		);
+		assert_eq!(
+			error("P1X"),
+			"invalid duration: unexpected 'X' at position 3"
+		);
+		assert_eq!(
+			error("P1DT"),
+			"invalid duration: unexpected 'T' at position 4"
+		);
+		assert_eq!(
+			error("P"),
+			"invalid duration: unexpected end of input at position 2"
+		);
+		assert_eq!(
+			error("ä1d"),
+			"invalid duration: unexpected 'ä' at position 1"
+		);
+	}
+
+	#[test]
+	fn empty_input_is_an_error() {
+		assert_eq!(
+			error(""),
+			"invalid duration: unexpected end of input at position 1"
+		);
+		assert_eq!(
+			error("   "),
+			"invalid duration: unexpected end of input at position 4"
+		);
+	}
+
+	#[test]
+	fn calendar_units_clamp_to_the_end_of_the_month() {
+		let add =
+			|duration: &str, start| parse_duration_str(duration).unwrap().add_to(start).unwrap();
+
+		assert_eq!(
+			add("1m", date(2024, Month::January, 31, 12)),
+			date(2024, Month::February, 29, 12)
+		);
+		assert_eq!(
+			add("1m", date(2023, Month::January, 31, 12)),
+			date(2023, Month::February, 28, 12)
+		);
+		assert_eq!(
+			add("1y", date(2024, Month::February, 29, 0)),
+			date(2025, Month::February, 28, 0)
+		);
+		assert_eq!(
+			add("13m", date(2024, Month::December, 15, 0)),
+			date(2026, Month::January, 15, 0)
+		);
+		assert_eq!(
+			add("1m 1d", date(2024, Month::January, 31, 0)),
+			date(2024, Month::March, 1, 0)
+		);
+	}
+
+	#[test]
+	fn fixed_units_convert_to_std_duration() {
+		assert_eq!(
+			parse_duration_str("1y 1m 1d").unwrap().to_std_duration(),
+			Duration::from_secs(SECS_PER_YEAR + SECS_PER_MONTH + SECS_PER_DAY)
+		);
+	}
 }
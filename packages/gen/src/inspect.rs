@@ -0,0 +1,408 @@
+use const_oid::db::{rfc5280, rfc5912, rfc8410, DB};
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use der::referenced::OwnedToRef;
+use der::{Decode, DecodePem};
+use k256::Secp256k1;
+use p256::NistP256;
+use p384::NistP384;
+use p521::NistP521;
+use rsa::traits::PublicKeyParts;
+use spki::SubjectPublicKeyInfoOwned;
+use time::OffsetDateTime;
+use x509_cert::ext::pkix::constraints::name::GeneralSubtree;
+use x509_cert::ext::pkix::name::DistributionPointName;
+use x509_cert::ext::pkix::{
+	AuthorityInfoAccessSyntax, AuthorityKeyIdentifier, BasicConstraints, CertificatePolicies,
+	CrlDistributionPoints, ExtendedKeyUsage, KeyUsage, NameConstraints, PolicyConstraints,
+	PolicyMappings, SubjectAltName, SubjectKeyIdentifier,
+};
+use x509_cert::ext::Extension;
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::alt_name::AltName;
+use super::certificate::to_offset_date_time;
+use super::name_constraints::Subtree;
+use super::policy::Policy;
+use super::scheme::brainpool::{BrainpoolP256r1, BrainpoolP384r1, BrainpoolP512r1};
+use super::scheme::rsa_pss;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct CertificateInfo {
+	pub version: u8,
+	pub serial_number: String,
+	pub signature_algorithm: String,
+	pub issuer: String,
+	pub subject: String,
+	pub not_before: OffsetDateTime,
+	pub not_after: OffsetDateTime,
+	pub public_key_algorithm: String,
+	pub public_key_size: Option<usize>,
+	pub extensions: Vec<ExtensionInfo>,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExtensionInfo {
+	pub oid: ObjectIdentifier,
+	pub name: String,
+	pub critical: bool,
+	pub value: ExtensionValue,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum ExtensionValue {
+	BasicConstraints {
+		ca: bool,
+		path_len_constraint: Option<u8>,
+	},
+	KeyUsage(Vec<String>),
+	ExtendedKeyUsage(Vec<String>),
+	SubjectAltName(Vec<AltName>),
+	SubjectKeyIdentifier(String),
+	AuthorityKeyIdentifier(Option<String>),
+	NameConstraints {
+		permitted: Vec<Subtree>,
+		excluded: Vec<Subtree>,
+	},
+	AuthorityInfoAccess(Vec<(String, AltName)>),
+	CrlDistributionPoints(Vec<AltName>),
+	CertificatePolicies(Vec<Policy>),
+	PolicyMappings(Vec<(ObjectIdentifier, ObjectIdentifier)>),
+	PolicyConstraints {
+		require_explicit_policy: Option<u32>,
+		inhibit_policy_mapping: Option<u32>,
+	},
+	InhibitAnyPolicy(u32),
+	Raw(String),
+}
+
+pub fn inspect(data: &[u8]) -> Result<CertificateInfo> {
+	let certificate = if data.starts_with(b"-----BEGIN") {
+		Certificate::from_pem(data)?
+	} else {
+		Certificate::from_der(data)?
+	};
+
+	inspect_certificate(&certificate)
+}
+
+pub fn inspect_certificate(certificate: &Certificate) -> Result<CertificateInfo> {
+	let tbs_certificate = &certificate.tbs_certificate;
+	let public_key = &tbs_certificate.subject_public_key_info;
+
+	Ok(CertificateInfo {
+		version: tbs_certificate.version as u8 + 1,
+		serial_number: tbs_certificate.serial_number.to_string(),
+		signature_algorithm: oid_name(certificate.signature_algorithm.oid),
+		issuer: tbs_certificate.issuer.to_string(),
+		subject: tbs_certificate.subject.to_string(),
+		not_before: to_offset_date_time(tbs_certificate.validity.not_before)?,
+		not_after: to_offset_date_time(tbs_certificate.validity.not_after)?,
+		public_key_algorithm: oid_name(public_key.algorithm.oid),
+		public_key_size: public_key_size(public_key),
+		extensions: tbs_certificate
+			.extensions
+			.iter()
+			.flatten()
+			.map(inspect_extension)
+			.collect::<Result<_>>()?,
+	})
+}
+
+fn inspect_extension(extension: &Extension) -> Result<ExtensionInfo> {
+	let bytes = extension.extn_value.as_bytes();
+
+	let value = match extension.extn_id {
+		BasicConstraints::OID => {
+			let basic_constraints = BasicConstraints::from_der(bytes)?;
+
+			ExtensionValue::BasicConstraints {
+				ca: basic_constraints.ca,
+				path_len_constraint: basic_constraints.path_len_constraint,
+			}
+		}
+		KeyUsage::OID => ExtensionValue::KeyUsage(
+			KeyUsage::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(|usage| format!("{usage:?}"))
+				.collect(),
+		),
+		ExtendedKeyUsage::OID => ExtensionValue::ExtendedKeyUsage(
+			ExtendedKeyUsage::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(oid_name)
+				.collect(),
+		),
+		SubjectAltName::OID => {
+			let names = SubjectAltName::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(AltName::try_from)
+				.collect::<Result<_>>();
+
+			match names {
+				Ok(names) => ExtensionValue::SubjectAltName(names),
+				Err(_) => ExtensionValue::Raw(hex(bytes)),
+			}
+		}
+		SubjectKeyIdentifier::OID => {
+			ExtensionValue::SubjectKeyIdentifier(hex(SubjectKeyIdentifier::from_der(bytes)?
+				.0
+				.as_bytes()))
+		}
+		AuthorityKeyIdentifier::OID => ExtensionValue::AuthorityKeyIdentifier(
+			AuthorityKeyIdentifier::from_der(bytes)?
+				.key_identifier
+				.map(|key_identifier| hex(key_identifier.as_bytes())),
+		),
+		NameConstraints::OID => {
+			let name_constraints = NameConstraints::from_der(bytes)?;
+
+			let to_subtrees = |subtrees: Option<Vec<GeneralSubtree>>| {
+				subtrees
+					.into_iter()
+					.flatten()
+					.map(Subtree::try_from)
+					.collect::<Result<Vec<_>>>()
+			};
+
+			match (
+				to_subtrees(name_constraints.permitted_subtrees),
+				to_subtrees(name_constraints.excluded_subtrees),
+			) {
+				(Ok(permitted), Ok(excluded)) => ExtensionValue::NameConstraints {
+					permitted,
+					excluded,
+				},
+				_ => ExtensionValue::Raw(hex(bytes)),
+			}
+		}
+		AuthorityInfoAccessSyntax::OID => {
+			let access_descriptions = AuthorityInfoAccessSyntax::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(|access_description| {
+					Ok((
+						oid_name(access_description.access_method),
+						AltName::try_from(access_description.access_location)?,
+					))
+				})
+				.collect::<Result<_>>();
+
+			match access_descriptions {
+				Ok(access_descriptions) => ExtensionValue::AuthorityInfoAccess(access_descriptions),
+				Err(_) => ExtensionValue::Raw(hex(bytes)),
+			}
+		}
+		CrlDistributionPoints::OID => {
+			let names = CrlDistributionPoints::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(|distribution_point| {
+					match (
+						distribution_point.distribution_point,
+						distribution_point.reasons,
+						distribution_point.crl_issuer,
+					) {
+						(Some(DistributionPointName::FullName(names)), None, None) => Ok(names),
+						_ => Err(Error::msg("unsupported CRL distribution point")),
+					}
+				})
+				.collect::<Result<Vec<_>>>()
+				.and_then(|names| {
+					names
+						.into_iter()
+						.flatten()
+						.map(AltName::try_from)
+						.collect::<Result<_>>()
+				});
+
+			match names {
+				Ok(names) => ExtensionValue::CrlDistributionPoints(names),
+				Err(_) => ExtensionValue::Raw(hex(bytes)),
+			}
+		}
+		CertificatePolicies::OID => {
+			let policies = CertificatePolicies::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(Policy::try_from)
+				.collect::<Result<_>>();
+
+			match policies {
+				Ok(policies) => ExtensionValue::CertificatePolicies(policies),
+				Err(_) => ExtensionValue::Raw(hex(bytes)),
+			}
+		}
+		PolicyMappings::OID => ExtensionValue::PolicyMappings(
+			PolicyMappings::from_der(bytes)?
+				.0
+				.into_iter()
+				.map(|mapping| (mapping.issuer_domain_policy, mapping.subject_domain_policy))
+				.collect(),
+		),
+		PolicyConstraints::OID => {
+			let policy_constraints = PolicyConstraints::from_der(bytes)?;
+
+			ExtensionValue::PolicyConstraints {
+				require_explicit_policy: policy_constraints.require_explicit_policy,
+				inhibit_policy_mapping: policy_constraints.inhibit_policy_mapping,
+			}
+		}
+		rfc5280::ID_CE_INHIBIT_ANY_POLICY => {
+			ExtensionValue::InhibitAnyPolicy(u32::from_der(bytes)?)
+		}
+		_ => ExtensionValue::Raw(hex(bytes)),
+	};
+
+	Ok(ExtensionInfo {
+		oid: extension.extn_id,
+		name: oid_name(extension.extn_id),
+		critical: extension.critical,
+		value,
+	})
+}
+
+fn public_key_size(public_key: &SubjectPublicKeyInfoOwned) -> Option<usize> {
+	match public_key.algorithm.oid {
+		rfc5912::RSA_ENCRYPTION | rfc5912::ID_RSASSA_PSS => rsa_pss::public_key(public_key)
+			.ok()
+			.map(|public_key| public_key.size() * 8),
+		rfc5912::ID_EC_PUBLIC_KEY => {
+			match public_key.algorithm.owned_to_ref().parameters_oid().ok()? {
+				NistP256::OID | Secp256k1::OID | BrainpoolP256r1::OID => Some(256),
+				NistP384::OID | BrainpoolP384r1::OID => Some(384),
+				BrainpoolP512r1::OID => Some(512),
+				NistP521::OID => Some(521),
+				_ => None,
+			}
+		}
+		rfc8410::ID_ED_25519 => Some(256),
+		_ => None,
+	}
+}
+
+fn oid_name(oid: ObjectIdentifier) -> String {
+	DB.by_oid(&oid)
+		.map(ToOwned::to_owned)
+		.unwrap_or_else(|| oid.to_string())
+}
+
+fn hex(bytes: &[u8]) -> String {
+	bytes
+		.iter()
+		.map(|byte| format!("{byte:02X}"))
+		.collect::<Vec<_>>()
+		.join(":")
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+	use std::time::Duration;
+
+	use x509_cert::name::Name;
+
+	use super::*;
+	use crate::certificate::TimeEncoding;
+	use crate::duration::CalendarDuration;
+	use crate::key_id::KeyIdMethod;
+	use crate::name_constraints::SubtreeType;
+	use crate::policy::CaPolicy;
+	use crate::scheme::ed25519::Ed25519;
+	use crate::self_signed::{gen_self_signed, SelfSignedCertOptions};
+	use crate::serial::SerialStrategy;
+
+	#[test]
+	fn policy_and_access_extensions_are_decoded() {
+		let permitted = Subtree::parse(SubtreeType::DNS, "*.example.com").unwrap();
+		let excluded = Subtree::parse(SubtreeType::IP, "10.0.0.0/8").unwrap();
+		let policy = Policy {
+			oid: ObjectIdentifier::new_unwrap("1.2.3.4"),
+			cps_uri: Some("https://example.com/cps".to_owned()),
+			user_notice: Some("notice".to_owned()),
+		};
+		let mapping = (
+			ObjectIdentifier::new_unwrap("1.2.3.4"),
+			ObjectIdentifier::new_unwrap("1.2.3.5"),
+		);
+
+		let (_, certificate) = gen_self_signed(
+			&Ed25519,
+			SelfSignedCertOptions {
+				issuer: Name::from_str("CN=test").unwrap(),
+				subject: Name::from_str("CN=test").unwrap(),
+				duration: Some(CalendarDuration::from(Duration::from_secs(24 * 60 * 60))),
+				not_before: None,
+				not_after: None,
+				backdate: Duration::ZERO,
+				time_encoding: TimeEncoding::default(),
+				serial: SerialStrategy::default(),
+				is_ca: true,
+				path_len_constraint: None,
+				san: Vec::new(),
+				key_usage: None,
+				key_usage_critical: None,
+				ext_key_usage: Vec::new(),
+				permitted_subtrees: vec![permitted.clone()],
+				excluded_subtrees: vec![excluded.clone()],
+				policies: vec![policy.clone()],
+				ca_policy: Some(CaPolicy {
+					mappings: vec![mapping],
+					require_explicit_policy: Some(1),
+					inhibit_policy_mapping: None,
+					inhibit_any_policy: Some(2),
+				}),
+				ocsp_urls: vec!["http://ocsp.example.com".to_owned()],
+				ca_issuers_urls: vec!["http://example.com/ca.crt".to_owned()],
+				crl_urls: vec!["http://example.com/ca.crl".to_owned()],
+				custom_extensions: Vec::new(),
+				key_id_method: KeyIdMethod::default(),
+			},
+		)
+		.unwrap();
+
+		let values: Vec<_> = inspect(certificate.as_bytes())
+			.unwrap()
+			.extensions
+			.into_iter()
+			.map(|extension| extension.value)
+			.collect();
+
+		for value in [
+			ExtensionValue::NameConstraints {
+				permitted: vec![permitted],
+				excluded: vec![excluded],
+			},
+			ExtensionValue::AuthorityInfoAccess(vec![
+				(
+					oid_name(rfc5280::ID_AD_OCSP),
+					AltName::URI("http://ocsp.example.com".to_owned()),
+				),
+				(
+					oid_name(rfc5280::ID_AD_CA_ISSUERS),
+					AltName::URI("http://example.com/ca.crt".to_owned()),
+				),
+			]),
+			ExtensionValue::CrlDistributionPoints(vec![AltName::URI(
+				"http://example.com/ca.crl".to_owned(),
+			)]),
+			ExtensionValue::CertificatePolicies(vec![policy]),
+			ExtensionValue::PolicyMappings(vec![mapping]),
+			ExtensionValue::PolicyConstraints {
+				require_explicit_policy: Some(1),
+				inhibit_policy_mapping: None,
+			},
+			ExtensionValue::InhibitAnyPolicy(2),
+		] {
+			assert!(values.contains(&value), "missing {value:?}");
+		}
+
+		assert!(!values
+			.iter()
+			.any(|value| matches!(value, ExtensionValue::Raw(_))));
+	}
+}
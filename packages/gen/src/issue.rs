@@ -0,0 +1,562 @@
+use std::time::Duration;
+
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use der::{Decode, DecodePem, Encode, EncodePem};
+use pkcs8::LineEnding;
+use time::OffsetDateTime;
+use x509_cert::ext::pkix::{BasicConstraints, ExtendedKeyUsage, KeyUsage, SubjectAltName};
+use x509_cert::ext::Extension;
+use x509_cert::name::Name;
+use x509_cert::request::{CertReq, CertReqInfo, ExtensionReq};
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::alt_name::AltName;
+use super::certificate::{gen_tbs_certificate, sign_certificate, OptionsBuilder, TimeEncoding};
+use super::context::Context;
+use super::custom_extension::CustomExtension;
+use super::duration::CalendarDuration;
+use super::key_id::{certificate_key_id, KeyIdMethod};
+use super::name_constraints::{check_name_constraints, Subtree};
+use super::policy::{CaPolicy, Policy};
+use super::scheme::{public_keys_match, verify_signature, PrivateKey, SignatureStrategy};
+use super::serial::SerialStrategy;
+use super::verify::find_extension;
+
+#[derive(Debug, Clone)]
+pub struct IssuedCertOptions {
+	pub subject: Name,
+	pub duration: Option<CalendarDuration>,
+	pub not_before: Option<OffsetDateTime>,
+	pub not_after: Option<OffsetDateTime>,
+	pub backdate: Duration,
+	pub time_encoding: TimeEncoding,
+	pub serial: SerialStrategy,
+	pub is_ca: bool,
+	pub path_len_constraint: Option<u8>,
+	pub san: Vec<AltName>,
+	pub key_usage: Option<KeyUsage>,
+	pub key_usage_critical: Option<bool>,
+	pub ext_key_usage: Vec<ObjectIdentifier>,
+	pub permitted_subtrees: Vec<Subtree>,
+	pub excluded_subtrees: Vec<Subtree>,
+	pub policies: Vec<Policy>,
+	pub ca_policy: Option<CaPolicy>,
+	pub ocsp_urls: Vec<String>,
+	pub ca_issuers_urls: Vec<String>,
+	pub crl_urls: Vec<String>,
+	pub custom_extensions: Vec<CustomExtension>,
+	pub key_id_method: KeyIdMethod,
+}
+
+pub struct Issuer<'a> {
+	pub strategy: &'a dyn SignatureStrategy,
+	pub key: &'a dyn PrivateKey,
+	pub certificate: &'a Certificate,
+}
+
+#[derive(Debug, Clone)]
+pub struct IssuePolicy {
+	pub allowed_san: Option<Vec<AltName>>,
+	pub allowed_ext_key_usage: Vec<ObjectIdentifier>,
+	pub allow_ca: bool,
+	pub max_duration: Duration,
+	pub key_usage: Option<KeyUsage>,
+	pub policies: Vec<Policy>,
+	pub ocsp_urls: Vec<String>,
+	pub ca_issuers_urls: Vec<String>,
+	pub crl_urls: Vec<String>,
+	pub key_id_method: KeyIdMethod,
+	pub serial: SerialStrategy,
+}
+
+impl IssuePolicy {
+	fn allows(&self, alt_name: &AltName) -> bool {
+		let Some(allowed_san) = self.allowed_san.as_ref() else {
+			return true;
+		};
+
+		allowed_san.iter().any(|allowed| match (allowed, alt_name) {
+			(AltName::DNS(pattern), AltName::DNS(name)) => dns_pattern_matches(pattern, name),
+			(allowed, alt_name) => allowed == alt_name,
+		})
+	}
+}
+
+pub fn gen_issued(
+	strategy: &dyn SignatureStrategy,
+	issuer: Issuer,
+	options: IssuedCertOptions,
+) -> Result<(String, String)> {
+	gen_issued_with_context(strategy, issuer, options, &mut Context::default())
+}
+
+pub fn gen_issued_with_context(
+	strategy: &dyn SignatureStrategy,
+	issuer: Issuer,
+	options: IssuedCertOptions,
+	context: &mut Context,
+) -> Result<(String, String)> {
+	check_issuer(&issuer)?;
+
+	let key = strategy.generate_key_with_rng(context.rng())?;
+
+	if options.is_ca {
+		check_issuer_path_len(&issuer, options.path_len_constraint)?;
+	}
+
+	check_name_constraints(issuer.certificate, &options.subject, &options.san)?;
+
+	let mut builder = OptionsBuilder::default()
+		.issuer(issuer.certificate.tbs_certificate.subject.clone())
+		.subject(options.subject)
+		.duration(options.duration)
+		.not_before(options.not_before)
+		.not_after(options.not_after)
+		.backdate(options.backdate)
+		.time_encoding(options.time_encoding)
+		.serial(options.serial)
+		.path_len_constraint(options.path_len_constraint)
+		.alt_names(options.san)
+		.key_usage(options.key_usage)
+		.key_usage_critical(options.key_usage_critical)
+		.ext_key_usage(options.ext_key_usage)
+		.permitted_subtrees(options.permitted_subtrees)
+		.excluded_subtrees(options.excluded_subtrees)
+		.policies(options.policies)
+		.ca_policy(options.ca_policy)
+		.ocsp_urls(options.ocsp_urls)
+		.ca_issuers_urls(options.ca_issuers_urls)
+		.crl_urls(options.crl_urls)
+		.custom_extensions(options.custom_extensions)
+		.key_id_method(options.key_id_method)
+		.authority_key_id(certificate_key_id(
+			issuer.certificate,
+			options.key_id_method,
+		)?);
+
+	if options.is_ca {
+		builder = builder.is_ca();
+	}
+
+	let tbs_cert = gen_tbs_certificate(
+		issuer.strategy,
+		key.to_subject_public_key_info()?,
+		builder.build()?,
+		context,
+	)?;
+
+	let crt = sign_certificate(issuer.strategy, issuer.key, tbs_cert, context)?;
+
+	Ok((key.to_pem(LineEnding::LF)?, crt.to_pem(LineEnding::LF)?))
+}
+
+pub fn sign_request(
+	issuer: Issuer,
+	request: &[u8],
+	duration: Duration,
+	policy: &IssuePolicy,
+) -> Result<String> {
+	sign_request_with_context(issuer, request, duration, policy, &mut Context::default())
+}
+
+pub fn sign_request_with_context(
+	issuer: Issuer,
+	request: &[u8],
+	duration: Duration,
+	policy: &IssuePolicy,
+	context: &mut Context,
+) -> Result<String> {
+	check_issuer(&issuer)?;
+
+	let request = if request.starts_with(b"-----BEGIN") {
+		CertReq::from_pem(request)?
+	} else {
+		CertReq::from_der(request)?
+	};
+
+	verify_signature(
+		&request.algorithm,
+		&request.info.public_key,
+		&request.info.to_der()?,
+		request.signature.raw_bytes(),
+	)?;
+
+	if duration > policy.max_duration {
+		return Err(Error::msg("requested duration exceeds the policy maximum"));
+	}
+
+	let mut alt_names = Vec::new();
+	let mut basic_constraints = None;
+	let mut key_usage = None;
+	let mut ext_key_usage = Vec::new();
+
+	for extension in requested_extensions(&request.info)? {
+		match extension.extn_id {
+			SubjectAltName::OID => {
+				for name in SubjectAltName::from_der(extension.extn_value.as_bytes())?.0 {
+					alt_names.push(AltName::try_from(name)?);
+				}
+			}
+			BasicConstraints::OID => {
+				basic_constraints =
+					Some(BasicConstraints::from_der(extension.extn_value.as_bytes())?);
+			}
+			KeyUsage::OID => {
+				key_usage = Some(KeyUsage::from_der(extension.extn_value.as_bytes())?);
+			}
+			ExtendedKeyUsage::OID => {
+				ext_key_usage = ExtendedKeyUsage::from_der(extension.extn_value.as_bytes())?.0;
+			}
+			_ => {}
+		}
+	}
+
+	let is_ca = basic_constraints
+		.as_ref()
+		.is_some_and(|basic_constraints| basic_constraints.ca);
+
+	if is_ca && !policy.allow_ca {
+		return Err(Error::msg(
+			"certificate authority issuance is not allowed by the policy",
+		));
+	}
+
+	if !is_ca
+		&& key_usage.is_some_and(|key_usage| key_usage.key_cert_sign() || key_usage.crl_sign())
+	{
+		return Err(Error::msg(
+			"certificate and CRL signing key usages are not allowed by the policy",
+		));
+	}
+
+	if let Some(oid) = ext_key_usage
+		.iter()
+		.find(|oid| !policy.allowed_ext_key_usage.contains(oid))
+	{
+		return Err(Error::msg(format!(
+			"extended key usage {oid} is not allowed by the policy"
+		)));
+	}
+
+	if let Some(alt_name) = alt_names.iter().find(|alt_name| !policy.allows(alt_name)) {
+		return Err(Error::msg(format!(
+			"{} name {} is not allowed by the policy",
+			alt_name.r#type(),
+			alt_name.text()
+		)));
+	}
+
+	check_name_constraints(issuer.certificate, &request.info.subject, &alt_names)?;
+
+	let path_len_constraint = basic_constraints
+		.filter(|_| is_ca)
+		.and_then(|basic_constraints| basic_constraints.path_len_constraint);
+
+	if is_ca {
+		check_issuer_path_len(&issuer, path_len_constraint)?;
+	}
+
+	let mut builder = OptionsBuilder::default()
+		.issuer(issuer.certificate.tbs_certificate.subject.clone())
+		.subject(request.info.subject)
+		.duration(CalendarDuration::from(duration))
+		.path_len_constraint(path_len_constraint)
+		.alt_names(alt_names)
+		.key_usage(policy.key_usage.or(key_usage))
+		.ext_key_usage(ext_key_usage)
+		.policies(policy.policies.clone())
+		.ocsp_urls(policy.ocsp_urls.clone())
+		.ca_issuers_urls(policy.ca_issuers_urls.clone())
+		.crl_urls(policy.crl_urls.clone())
+		.key_id_method(policy.key_id_method)
+		.serial(policy.serial.clone())
+		.authority_key_id(certificate_key_id(
+			issuer.certificate,
+			policy.key_id_method,
+		)?);
+
+	if is_ca {
+		builder = builder.is_ca();
+	}
+
+	let tbs_cert = gen_tbs_certificate(
+		issuer.strategy,
+		request.info.public_key,
+		builder.build()?,
+		context,
+	)?;
+
+	let crt = sign_certificate(issuer.strategy, issuer.key, tbs_cert, context)?;
+
+	Ok(crt.to_pem(LineEnding::LF)?)
+}
+
+fn check_issuer(issuer: &Issuer) -> Result<()> {
+	if !public_keys_match(
+		&issuer.key.to_subject_public_key_info()?,
+		&issuer.certificate.tbs_certificate.subject_public_key_info,
+	)? {
+		return Err(Error::msg("issuer key does not match issuer certificate"));
+	}
+
+	match find_extension::<BasicConstraints>(issuer.certificate)? {
+		Some(basic_constraints) if basic_constraints.ca => Ok(()),
+		_ => Err(Error::msg(
+			"issuer certificate is not a certificate authority",
+		)),
+	}
+}
+
+fn check_issuer_path_len(issuer: &Issuer, path_len_constraint: Option<u8>) -> Result<()> {
+	let issuer_path_len = find_extension::<BasicConstraints>(issuer.certificate)?
+		.and_then(|basic_constraints| basic_constraints.path_len_constraint);
+
+	match (issuer_path_len, path_len_constraint) {
+		(Some(0), _) => Err(Error::msg(
+			"issuer path length constraint does not allow intermediate CAs",
+		)),
+		(Some(issuer_path_len), None) => Err(Error::msg(format!(
+			"intermediate CA requires a path length constraint below {issuer_path_len}"
+		))),
+		(Some(issuer_path_len), Some(path_len)) if path_len >= issuer_path_len => Err(Error::msg(
+			format!("intermediate CA path length constraint must be below {issuer_path_len}"),
+		)),
+		_ => Ok(()),
+	}
+}
+
+fn dns_pattern_matches(pattern: &str, name: &str) -> bool {
+	match pattern.strip_prefix("*.") {
+		Some(suffix) if !suffix.is_empty() && !suffix.contains('*') => {
+			name.split_once('.').is_some_and(|(label, domain)| {
+				!label.is_empty() && domain.eq_ignore_ascii_case(suffix)
+			})
+		}
+		Some(_) => false,
+		None => !pattern.contains('*') && pattern.eq_ignore_ascii_case(name),
+	}
+}
+
+fn requested_extensions(info: &CertReqInfo) -> Result<Vec<Extension>> {
+	let mut extensions = Vec::new();
+
+	for attribute in info.attributes.iter() {
+		if attribute.oid != ExtensionReq::OID {
+			continue;
+		}
+
+		for value in attribute.values.iter() {
+			extensions.extend(ExtensionReq::from_der(&value.to_der()?)?.0);
+		}
+	}
+
+	Ok(extensions)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use const_oid::db::rfc5280;
+	use der::DecodePem;
+	use x509_cert::attr::Attribute;
+	use x509_cert::ext::pkix::KeyUsages;
+
+	use super::*;
+	use crate::certificate::ToExtension;
+	use crate::csr::{gen_csr_info, sign_csr, CsrOptions};
+	use crate::scheme::ed25519::Ed25519;
+	use crate::scheme::load_key;
+	use crate::self_signed::{gen_self_signed, SelfSignedCertOptions};
+
+	const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+	fn ca() -> (String, Certificate) {
+		let (key, certificate) = gen_self_signed(
+			&Ed25519,
+			SelfSignedCertOptions {
+				issuer: Name::from_str("CN=Test CA").unwrap(),
+				subject: Name::from_str("CN=Test CA").unwrap(),
+				duration: Some(CalendarDuration::from(DAY)),
+				not_before: None,
+				not_after: None,
+				backdate: Duration::ZERO,
+				time_encoding: TimeEncoding::default(),
+				serial: SerialStrategy::default(),
+				is_ca: true,
+				path_len_constraint: None,
+				san: Vec::new(),
+				key_usage: None,
+				key_usage_critical: None,
+				ext_key_usage: Vec::new(),
+				permitted_subtrees: Vec::new(),
+				excluded_subtrees: Vec::new(),
+				policies: Vec::new(),
+				ca_policy: None,
+				ocsp_urls: Vec::new(),
+				ca_issuers_urls: Vec::new(),
+				crl_urls: Vec::new(),
+				custom_extensions: Vec::new(),
+				key_id_method: KeyIdMethod::default(),
+			},
+		)
+		.unwrap();
+
+		(key, Certificate::from_pem(certificate).unwrap())
+	}
+
+	fn policy() -> IssuePolicy {
+		IssuePolicy {
+			allowed_san: None,
+			allowed_ext_key_usage: vec![rfc5280::ID_KP_SERVER_AUTH, rfc5280::ID_KP_CLIENT_AUTH],
+			allow_ca: false,
+			max_duration: DAY,
+			key_usage: None,
+			policies: Vec::new(),
+			ocsp_urls: Vec::new(),
+			ca_issuers_urls: Vec::new(),
+			crl_urls: Vec::new(),
+			key_id_method: KeyIdMethod::default(),
+			serial: SerialStrategy::default(),
+		}
+	}
+
+	fn request(extensions: Vec<Extension>) -> Vec<u8> {
+		let key = Ed25519.generate_key().unwrap();
+
+		let mut info = gen_csr_info(
+			key.as_ref(),
+			CsrOptions {
+				subject: Name::from_str("CN=leaf").unwrap(),
+				san: Vec::new(),
+			},
+		)
+		.unwrap();
+
+		info.attributes
+			.insert(Attribute::try_from(ExtensionReq(extensions)).unwrap())
+			.unwrap();
+
+		sign_csr(&Ed25519, key.as_ref(), info, &mut Context::default())
+			.unwrap()
+			.to_der()
+			.unwrap()
+	}
+
+	fn sign(request: &[u8], policy: &IssuePolicy) -> Result<Certificate> {
+		let (key, certificate) = ca();
+		let (strategy, key) = load_key(key.as_bytes()).unwrap();
+
+		let issuer = Issuer {
+			strategy: strategy.as_ref(),
+			key: key.as_ref(),
+			certificate: &certificate,
+		};
+
+		Ok(Certificate::from_pem(sign_request(
+			issuer, request, DAY, policy,
+		)?)?)
+	}
+
+	#[test]
+	fn request_with_allowed_ext_key_usage_is_signed() {
+		let ext_key_usage = ExtendedKeyUsage(vec![rfc5280::ID_KP_SERVER_AUTH]);
+		let request = request(vec![ext_key_usage.to_extension(false).unwrap()]);
+
+		let certificate = sign(&request, &policy()).unwrap();
+
+		assert_eq!(
+			find_extension::<ExtendedKeyUsage>(&certificate).unwrap(),
+			Some(ext_key_usage)
+		);
+	}
+
+	#[test]
+	fn request_with_forbidden_ext_key_usage_is_refused() {
+		for oid in [
+			rfc5280::ID_KP_CODE_SIGNING,
+			rfc5280::ID_KP_OCSP_SIGNING,
+			rfc5280::ID_KP_TIME_STAMPING,
+		] {
+			let ext_key_usage = ExtendedKeyUsage(vec![rfc5280::ID_KP_SERVER_AUTH, oid]);
+			let request = request(vec![ext_key_usage.to_extension(false).unwrap()]);
+
+			assert!(sign(&request, &policy()).is_err());
+		}
+	}
+
+	#[test]
+	fn request_with_certificate_signing_key_usage_is_refused() {
+		for usage in [KeyUsages::KeyCertSign, KeyUsages::CRLSign] {
+			let key_usage = KeyUsage(KeyUsages::DigitalSignature | usage);
+			let request = request(vec![key_usage.to_extension(true).unwrap()]);
+
+			assert!(sign(&request, &policy()).is_err());
+			assert!(sign(
+				&request,
+				&IssuePolicy {
+					key_usage: Some(KeyUsage(KeyUsages::DigitalSignature.into())),
+					..policy()
+				}
+			)
+			.is_err());
+		}
+	}
+
+	#[test]
+	fn request_for_ca_requires_policy() {
+		let basic_constraints = BasicConstraints {
+			ca: true,
+			path_len_constraint: Some(0),
+		};
+		let key_usage = KeyUsage(KeyUsages::KeyCertSign | KeyUsages::CRLSign);
+		let request = request(vec![
+			basic_constraints.to_extension(true).unwrap(),
+			key_usage.to_extension(true).unwrap(),
+		]);
+
+		assert!(sign(&request, &policy()).is_err());
+
+		let certificate = sign(
+			&request,
+			&IssuePolicy {
+				allow_ca: true,
+				..policy()
+			},
+		)
+		.unwrap();
+
+		assert_eq!(
+			find_extension::<BasicConstraints>(&certificate).unwrap(),
+			Some(basic_constraints)
+		);
+		assert_eq!(
+			find_extension::<KeyUsage>(&certificate).unwrap(),
+			Some(key_usage)
+		);
+	}
+
+	#[test]
+	fn wildcard_pattern_matches_one_label() {
+		assert!(dns_pattern_matches("*.example.com", "a.example.com"));
+		assert!(dns_pattern_matches("*.example.com", "A.Example.COM"));
+		assert!(!dns_pattern_matches("*.example.com", "example.com"));
+		assert!(!dns_pattern_matches("*.example.com", "a.b.example.com"));
+		assert!(!dns_pattern_matches("*.example.com", "aexample.com"));
+	}
+
+	#[test]
+	fn wildcard_pattern_requires_label_boundary() {
+		assert!(!dns_pattern_matches("*example.com", "evilexample.com"));
+		assert!(!dns_pattern_matches("*example.com", "a.example.com"));
+		assert!(!dns_pattern_matches("*.", "a."));
+	}
+
+	#[test]
+	fn exact_pattern_matches_case_insensitively() {
+		assert!(dns_pattern_matches("www.example.com", "WWW.example.com"));
+		assert!(!dns_pattern_matches("www.example.com", "a.www.example.com"));
+	}
+}
@@ -0,0 +1,56 @@
+use der::asn1::OctetString;
+use der::Encode;
+use enum_iterator::Sequence;
+use parse_display::Display;
+use sha1::Sha1;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use spki::SubjectPublicKeyInfoOwned;
+use x509_cert::ext::pkix::SubjectKeyIdentifier;
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::verify::find_extension;
+
+const TRUNCATED_LENGTH: usize = 20;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Display, Sequence)]
+pub enum KeyIdMethod {
+	#[default]
+	#[display("SHA-1 (RFC 5280)")]
+	Sha1,
+	#[display("Truncated SHA-256 (RFC 7093)")]
+	TruncatedSha256,
+	#[display("Truncated SHA-384 (RFC 7093)")]
+	TruncatedSha384,
+	#[display("Truncated SHA-512 (RFC 7093)")]
+	TruncatedSha512,
+	#[display("SHA-256 of SubjectPublicKeyInfo (RFC 7093)")]
+	SpkiSha256,
+}
+
+impl KeyIdMethod {
+	pub fn key_id(&self, public_key: &SubjectPublicKeyInfoOwned) -> Result<OctetString> {
+		let key_bits = public_key.subject_public_key.raw_bytes();
+
+		let key_id = match self {
+			KeyIdMethod::Sha1 => Sha1::digest(key_bits).to_vec(),
+			KeyIdMethod::TruncatedSha256 => Sha256::digest(key_bits)[..TRUNCATED_LENGTH].to_vec(),
+			KeyIdMethod::TruncatedSha384 => Sha384::digest(key_bits)[..TRUNCATED_LENGTH].to_vec(),
+			KeyIdMethod::TruncatedSha512 => Sha512::digest(key_bits)[..TRUNCATED_LENGTH].to_vec(),
+			KeyIdMethod::SpkiSha256 => Sha256::digest(public_key.to_der()?).to_vec(),
+		};
+
+		Ok(OctetString::new(key_id)?)
+	}
+}
+
+pub(crate) fn certificate_key_id(
+	certificate: &Certificate,
+	method: KeyIdMethod,
+) -> Result<OctetString> {
+	match find_extension::<SubjectKeyIdentifier>(certificate)? {
+		Some(subject_key_identifier) => Ok(subject_key_identifier.0),
+		None => method.key_id(&certificate.tbs_certificate.subject_public_key_info),
+	}
+}
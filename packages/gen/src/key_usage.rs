@@ -0,0 +1,98 @@
+use const_oid::db::{rfc5280, rfc5912};
+use const_oid::ObjectIdentifier;
+use enum_iterator::Sequence;
+use parse_display::Display;
+use spki::SubjectPublicKeyInfoOwned;
+use x509_cert::ext::pkix::{KeyUsage, KeyUsages};
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, Sequence)]
+pub enum KeyUsageBit {
+	#[display("Digital Signature")]
+	DigitalSignature,
+	#[display("Non Repudiation")]
+	NonRepudiation,
+	#[display("Key Encipherment")]
+	KeyEncipherment,
+	#[display("Data Encipherment")]
+	DataEncipherment,
+	#[display("Key Agreement")]
+	KeyAgreement,
+	#[display("Certificate Sign")]
+	KeyCertSign,
+	#[display("CRL Sign")]
+	CRLSign,
+	#[display("Encipher Only")]
+	EncipherOnly,
+	#[display("Decipher Only")]
+	DecipherOnly,
+}
+
+impl From<KeyUsageBit> for KeyUsages {
+	fn from(value: KeyUsageBit) -> KeyUsages {
+		match value {
+			KeyUsageBit::DigitalSignature => KeyUsages::DigitalSignature,
+			KeyUsageBit::NonRepudiation => KeyUsages::NonRepudiation,
+			KeyUsageBit::KeyEncipherment => KeyUsages::KeyEncipherment,
+			KeyUsageBit::DataEncipherment => KeyUsages::DataEncipherment,
+			KeyUsageBit::KeyAgreement => KeyUsages::KeyAgreement,
+			KeyUsageBit::KeyCertSign => KeyUsages::KeyCertSign,
+			KeyUsageBit::CRLSign => KeyUsages::CRLSign,
+			KeyUsageBit::EncipherOnly => KeyUsages::EncipherOnly,
+			KeyUsageBit::DecipherOnly => KeyUsages::DecipherOnly,
+		}
+	}
+}
+
+pub fn key_usage_from_bits(bits: impl IntoIterator<Item = KeyUsageBit>) -> KeyUsage {
+	let mut key_usage = KeyUsage(Default::default());
+
+	for bit in bits {
+		key_usage.0 |= KeyUsages::from(bit);
+	}
+
+	key_usage
+}
+
+pub fn default_key_usage(public_key: &SubjectPublicKeyInfoOwned, is_ca: bool) -> KeyUsage {
+	if is_ca {
+		return KeyUsage::from(
+			KeyUsages::DigitalSignature | KeyUsages::KeyCertSign | KeyUsages::CRLSign,
+		);
+	}
+
+	match public_key.algorithm.oid {
+		rfc5912::RSA_ENCRYPTION => {
+			KeyUsage::from(KeyUsages::DigitalSignature | KeyUsages::KeyEncipherment)
+		}
+		_ => KeyUsage(KeyUsages::DigitalSignature.into()),
+	}
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, Sequence)]
+pub enum KeyPurpose {
+	#[display("TLS Web Server Authentication")]
+	ServerAuth,
+	#[display("TLS Web Client Authentication")]
+	ClientAuth,
+	#[display("Code Signing")]
+	CodeSigning,
+	#[display("E-mail Protection")]
+	EmailProtection,
+	#[display("Time Stamping")]
+	TimeStamping,
+	#[display("OCSP Signing")]
+	OcspSigning,
+}
+
+impl From<KeyPurpose> for ObjectIdentifier {
+	fn from(value: KeyPurpose) -> ObjectIdentifier {
+		match value {
+			KeyPurpose::ServerAuth => rfc5280::ID_KP_SERVER_AUTH,
+			KeyPurpose::ClientAuth => rfc5280::ID_KP_CLIENT_AUTH,
+			KeyPurpose::CodeSigning => rfc5280::ID_KP_CODE_SIGNING,
+			KeyPurpose::EmailProtection => rfc5280::ID_KP_EMAIL_PROTECTION,
+			KeyPurpose::TimeStamping => rfc5280::ID_KP_TIME_STAMPING,
+			KeyPurpose::OcspSigning => rfc5280::ID_KP_OCSP_SIGNING,
+		}
+	}
+}
@@ -1,5 +1,17 @@
 pub mod alt_name;
 pub mod certificate;
+pub mod context;
+pub mod csr;
+pub mod custom_extension;
 pub mod duration;
+pub mod inspect;
+pub mod issue;
+pub mod key_id;
+pub mod key_usage;
+pub mod name_constraints;
+pub mod pfx;
+pub mod policy;
 pub mod scheme;
 pub mod self_signed;
+pub mod serial;
+pub mod verify;
@@ -0,0 +1,384 @@
+use std::net::IpAddr;
+use std::str::FromStr;
+
+use der::asn1::{Ia5String, OctetString};
+use enum_iterator::Sequence;
+use num_enum::{FromPrimitive, IntoPrimitive};
+use parse_display::Display;
+use x509_cert::ext::pkix::constraints::name::{GeneralSubtree, NameConstraints};
+use x509_cert::ext::pkix::name::GeneralName;
+use x509_cert::name::Name;
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::alt_name::AltName;
+use super::verify::find_extension;
+
+#[derive(
+	Debug, Clone, Copy, PartialEq, Display, Sequence, Default, FromPrimitive, IntoPrimitive,
+)]
+#[repr(u8)]
+pub enum SubtreeType {
+	#[default]
+	DNS,
+	IP,
+	Email,
+	URI,
+	#[display("Directory Name")]
+	DirectoryName,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum Subtree {
+	DNS(String),
+	IP(IpAddr, u8),
+	Email(String),
+	URI(String),
+	DirectoryName(Name),
+}
+
+impl Subtree {
+	pub fn parse(typ: SubtreeType, val: &str) -> Result<Self> {
+		Ok(match typ {
+			SubtreeType::DNS => {
+				let constraint = match val.strip_prefix("*.") {
+					Some(suffix) => format!(".{suffix}"),
+					None => val.to_owned(),
+				};
+
+				if constraint.contains('*') || constraint == "." {
+					return Err(Error::msg(format!(
+						"invalid DNS constraint {val}, wildcards must have the form *.domain"
+					)));
+				}
+
+				Subtree::DNS(constraint)
+			}
+			SubtreeType::IP => {
+				let (ip, prefix) = match val.split_once('/') {
+					Some((ip, prefix)) => (IpAddr::from_str(ip)?, u8::from_str(prefix)?),
+					None => {
+						let ip = IpAddr::from_str(val)?;
+
+						(ip, max_prefix(&ip))
+					}
+				};
+
+				if prefix > max_prefix(&ip) {
+					return Err(Error::msg(format!("invalid prefix length /{prefix}")));
+				}
+
+				Subtree::IP(mask(&ip, prefix), prefix)
+			}
+			SubtreeType::Email => Subtree::Email(val.to_owned()),
+			SubtreeType::URI => Subtree::URI(val.to_owned()),
+			SubtreeType::DirectoryName => Subtree::DirectoryName(Name::from_str(val)?),
+		})
+	}
+
+	pub fn r#type(&self) -> &'static str {
+		match self {
+			Subtree::DNS(_) => "DNS",
+			Subtree::IP(_, _) => "IP",
+			Subtree::Email(_) => "Email",
+			Subtree::URI(_) => "URI",
+			Subtree::DirectoryName(_) => "Directory Name",
+		}
+	}
+
+	pub fn text(&self) -> String {
+		match self {
+			Subtree::DNS(name) => name.clone(),
+			Subtree::IP(ip, prefix) => format!("{ip}/{prefix}"),
+			Subtree::Email(email) => email.clone(),
+			Subtree::URI(uri) => uri.clone(),
+			Subtree::DirectoryName(name) => name.to_string(),
+		}
+	}
+
+	fn matches_alt_name(&self, alt_name: &AltName) -> Option<bool> {
+		match (self, alt_name) {
+			(Subtree::DNS(constraint), AltName::DNS(name)) => Some(dns_matches(constraint, name)),
+			(Subtree::IP(base, prefix), AltName::IP(ip)) => Some(
+				matches!(
+					(base, ip),
+					(IpAddr::V4(_), IpAddr::V4(_)) | (IpAddr::V6(_), IpAddr::V6(_))
+				) && mask(ip, *prefix) == *base,
+			),
+			(Subtree::Email(constraint), AltName::Email(email)) => {
+				Some(email_matches(constraint, email))
+			}
+			(Subtree::URI(constraint), AltName::URI(uri)) => Some(uri_matches(constraint, uri)),
+			(Subtree::DirectoryName(base), AltName::DirectoryName(name)) => {
+				Some(name.0.starts_with(&base.0))
+			}
+			_ => None,
+		}
+	}
+
+	fn matches_subject(&self, subject: &Name) -> Option<bool> {
+		match self {
+			Subtree::DirectoryName(base) => Some(subject.0.starts_with(&base.0)),
+			_ => None,
+		}
+	}
+}
+
+impl TryFrom<Subtree> for GeneralSubtree {
+	type Error = Error;
+
+	fn try_from(value: Subtree) -> Result<GeneralSubtree, Self::Error> {
+		let base = match value {
+			Subtree::DNS(name) => GeneralName::DnsName(Ia5String::new(&name)?),
+			Subtree::IP(ip, prefix) => {
+				let mut bytes = match ip {
+					IpAddr::V4(ip) => ip.octets().to_vec(),
+					IpAddr::V6(ip) => ip.octets().to_vec(),
+				};
+
+				bytes.extend(prefix_mask(bytes.len(), prefix));
+
+				GeneralName::IpAddress(OctetString::new(bytes)?)
+			}
+			Subtree::Email(email) => GeneralName::Rfc822Name(Ia5String::new(&email)?),
+			Subtree::URI(uri) => GeneralName::UniformResourceIdentifier(Ia5String::new(&uri)?),
+			Subtree::DirectoryName(name) => GeneralName::DirectoryName(name),
+		};
+
+		Ok(GeneralSubtree {
+			base,
+			minimum: 0,
+			maximum: None,
+		})
+	}
+}
+
+impl TryFrom<GeneralSubtree> for Subtree {
+	type Error = Error;
+
+	fn try_from(value: GeneralSubtree) -> Result<Subtree, Self::Error> {
+		Ok(match value.base {
+			GeneralName::DnsName(name) => Subtree::DNS(name.to_string()),
+			GeneralName::IpAddress(ip) => {
+				let bytes = ip.as_bytes();
+				let (address, mask) = bytes.split_at(bytes.len() / 2);
+
+				let ip = match address {
+					&[a, b, c, d] => IpAddr::from([a, b, c, d]),
+					address => IpAddr::from(
+						<[u8; 16]>::try_from(address)
+							.map_err(|_| Error::msg("invalid IP address length"))?,
+					),
+				};
+				let prefix = mask.iter().map(|byte| byte.count_ones() as u8).sum();
+
+				Subtree::IP(ip, prefix)
+			}
+			GeneralName::Rfc822Name(email) => Subtree::Email(email.to_string()),
+			GeneralName::UniformResourceIdentifier(uri) => Subtree::URI(uri.to_string()),
+			GeneralName::DirectoryName(name) => Subtree::DirectoryName(name),
+			_ => return Err(Error::msg("unsupported name constraint type")),
+		})
+	}
+}
+
+pub(crate) fn to_name_constraints(
+	permitted: Vec<Subtree>,
+	excluded: Vec<Subtree>,
+) -> Result<NameConstraints> {
+	let to_subtrees = |subtrees: Vec<Subtree>| {
+		(!subtrees.is_empty())
+			.then(|| subtrees.into_iter().map(TryFrom::try_from).collect())
+			.transpose()
+	};
+
+	Ok(NameConstraints {
+		permitted_subtrees: to_subtrees(permitted)?,
+		excluded_subtrees: to_subtrees(excluded)?,
+	})
+}
+
+pub(crate) fn check_name_constraints(
+	issuer: &Certificate,
+	subject: &Name,
+	alt_names: &[AltName],
+) -> Result<()> {
+	let Some(name_constraints) = find_extension::<NameConstraints>(issuer)? else {
+		return Ok(());
+	};
+
+	let to_subtrees = |subtrees: Option<Vec<GeneralSubtree>>| {
+		subtrees
+			.into_iter()
+			.flatten()
+			.map(Subtree::try_from)
+			.collect::<Result<Vec<_>>>()
+	};
+
+	let permitted = to_subtrees(name_constraints.permitted_subtrees)?;
+	let excluded = to_subtrees(name_constraints.excluded_subtrees)?;
+
+	let allowed = |matches: &dyn Fn(&Subtree) -> Option<bool>| {
+		let mut permitted_matches = permitted.iter().filter_map(matches).peekable();
+
+		(permitted_matches.peek().is_none() || permitted_matches.any(|matched| matched))
+			&& !excluded.iter().filter_map(matches).any(|matched| matched)
+	};
+
+	if !subject.0.is_empty() && !allowed(&|subtree| subtree.matches_subject(subject)) {
+		return Err(Error::msg(format!(
+			"subject {subject} is not allowed by the issuer name constraints"
+		)));
+	}
+
+	if let Some(alt_name) = alt_names
+		.iter()
+		.find(|alt_name| !allowed(&|subtree| subtree.matches_alt_name(alt_name)))
+	{
+		return Err(Error::msg(format!(
+			"{} name {} is not allowed by the issuer name constraints",
+			alt_name.r#type(),
+			alt_name.text()
+		)));
+	}
+
+	Ok(())
+}
+
+fn dns_matches(constraint: &str, name: &str) -> bool {
+	let constraint = constraint.to_ascii_lowercase();
+	let name = name.to_ascii_lowercase();
+
+	if constraint.starts_with('.') {
+		name.ends_with(&constraint)
+	} else {
+		name == constraint || name.ends_with(&format!(".{constraint}"))
+	}
+}
+
+fn email_matches(constraint: &str, email: &str) -> bool {
+	let Some((_, domain)) = email.rsplit_once('@') else {
+		return false;
+	};
+
+	if constraint.contains('@') {
+		match constraint.rsplit_once('@') {
+			Some((local, constraint_domain)) => {
+				email.starts_with(&format!("{local}@"))
+					&& domain.eq_ignore_ascii_case(constraint_domain)
+			}
+			None => false,
+		}
+	} else if constraint.starts_with('.') {
+		domain
+			.to_ascii_lowercase()
+			.ends_with(&constraint.to_ascii_lowercase())
+	} else {
+		domain.eq_ignore_ascii_case(constraint)
+	}
+}
+
+fn uri_matches(constraint: &str, uri: &str) -> bool {
+	let Some((_, rest)) = uri.split_once("://") else {
+		return false;
+	};
+
+	let authority = rest.split(['/', '?', '#']).next().unwrap_or_default();
+	let host = authority
+		.rsplit_once('@')
+		.map_or(authority, |(_, host)| host);
+	let host = match host.rsplit_once(':') {
+		Some((host, port)) if port.chars().all(|c| c.is_ascii_digit()) => host,
+		_ => host,
+	};
+
+	if constraint.starts_with('.') {
+		host.to_ascii_lowercase()
+			.ends_with(&constraint.to_ascii_lowercase())
+	} else {
+		host.eq_ignore_ascii_case(constraint)
+	}
+}
+
+fn max_prefix(ip: &IpAddr) -> u8 {
+	match ip {
+		IpAddr::V4(_) => 32,
+		IpAddr::V6(_) => 128,
+	}
+}
+
+fn prefix_mask(len: usize, prefix: u8) -> Vec<u8> {
+	(0..len)
+		.map(|index| {
+			let bits = (prefix as usize).saturating_sub(index * 8).min(8);
+
+			(0xFF_u16 << (8 - bits)) as u8
+		})
+		.collect()
+}
+
+fn mask(ip: &IpAddr, prefix: u8) -> IpAddr {
+	match ip {
+		IpAddr::V4(ip) => {
+			let mut octets = ip.octets();
+
+			for (octet, mask) in octets.iter_mut().zip(prefix_mask(4, prefix)) {
+				*octet &= mask;
+			}
+
+			IpAddr::from(octets)
+		}
+		IpAddr::V6(ip) => {
+			let mut octets = ip.octets();
+
+			for (octet, mask) in octets.iter_mut().zip(prefix_mask(16, prefix)) {
+				*octet &= mask;
+			}
+
+			IpAddr::from(octets)
+		}
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	#[test]
+	fn wildcard_constraint_requires_label_boundary() {
+		assert!(Subtree::parse(SubtreeType::DNS, "*example.com").is_err());
+		assert!(Subtree::parse(SubtreeType::DNS, "a.*.example.com").is_err());
+		assert!(Subtree::parse(SubtreeType::DNS, "*.").is_err());
+
+		let subtree = Subtree::parse(SubtreeType::DNS, "*.example.com").unwrap();
+
+		assert_eq!(subtree, Subtree::DNS(".example.com".to_owned()));
+		assert_eq!(
+			subtree.matches_alt_name(&AltName::DNS("evilexample.com".to_owned())),
+			Some(false)
+		);
+		assert_eq!(
+			subtree.matches_alt_name(&AltName::DNS("example.com".to_owned())),
+			Some(false)
+		);
+		assert_eq!(
+			subtree.matches_alt_name(&AltName::DNS("a.example.com".to_owned())),
+			Some(true)
+		);
+	}
+
+	#[test]
+	fn host_constraint_matches_on_dot_boundary() {
+		let subtree = Subtree::parse(SubtreeType::DNS, "example.com").unwrap();
+
+		assert_eq!(
+			subtree.matches_alt_name(&AltName::DNS("evilexample.com".to_owned())),
+			Some(false)
+		);
+		assert_eq!(
+			subtree.matches_alt_name(&AltName::DNS("a.example.com".to_owned())),
+			Some(true)
+		);
+	}
+}
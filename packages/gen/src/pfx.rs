@@ -0,0 +1,145 @@
+use cms::content_info::ContentInfo;
+use const_oid::db::{rfc5911, rfc5912};
+use const_oid::ObjectIdentifier;
+use der::asn1::{BmpString, OctetString, SetOfVec};
+use der::{Any, Encode};
+use hmac::{Hmac, Mac};
+use pkcs12::cert_type::CertBag;
+use pkcs12::digest_info::DigestInfo;
+use pkcs12::kdf::{derive_key_utf8, Pkcs12KeyType};
+use pkcs12::mac_data::MacData;
+use pkcs12::pfx::{Pfx, Version};
+use pkcs12::safe_bag::{SafeBag, SafeContents};
+use pkcs12::{PKCS_12_CERT_BAG_OID, PKCS_12_PKCS8_KEY_BAG_OID, PKCS_12_X509_CERT_OID};
+use sha2::{Digest, Sha256};
+use spki::AlgorithmIdentifierOwned;
+use x509_cert::attr::{Attribute, Attributes};
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::context::Context;
+use super::scheme::PrivateKey;
+
+const PKCS_9_AT_FRIENDLY_NAME: ObjectIdentifier =
+	ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.20");
+const PKCS_9_AT_LOCAL_KEY_ID: ObjectIdentifier =
+	ObjectIdentifier::new_unwrap("1.2.840.113549.1.9.21");
+
+const ITERATIONS: u32 = 10_000;
+
+#[derive(Debug, Clone)]
+pub struct PfxOptions {
+	pub friendly_name: Option<String>,
+	pub password: String,
+	pub chain: Vec<Certificate>,
+}
+
+pub fn gen_pfx(
+	key: &dyn PrivateKey,
+	certificate: &Certificate,
+	options: PfxOptions,
+) -> Result<Vec<u8>> {
+	gen_pfx_with_context(key, certificate, options, &mut Context::default())
+}
+
+pub fn gen_pfx_with_context(
+	key: &dyn PrivateKey,
+	certificate: &Certificate,
+	options: PfxOptions,
+	context: &mut Context,
+) -> Result<Vec<u8>> {
+	let certificate_der = certificate.to_der()?;
+	let local_key_id = Sha256::digest(&certificate_der);
+
+	let mut attributes = vec![attribute(
+		PKCS_9_AT_LOCAL_KEY_ID,
+		Any::encode_from(&OctetString::new(local_key_id.as_slice())?)?,
+	)?];
+
+	if let Some(friendly_name) = options.friendly_name.as_deref() {
+		attributes.push(attribute(
+			PKCS_9_AT_FRIENDLY_NAME,
+			Any::encode_from(&BmpString::from_utf8(friendly_name)?)?,
+		)?);
+	}
+
+	let attributes = Attributes::try_from(attributes)?;
+
+	let encrypted_key = key.to_encrypted_der_with_rng(&options.password, context.rng())?;
+
+	let key_bags: SafeContents = vec![SafeBag {
+		bag_id: PKCS_12_PKCS8_KEY_BAG_OID,
+		bag_value: encrypted_key.as_bytes().to_vec(),
+		bag_attributes: Some(attributes.clone()),
+	}];
+
+	let mut cert_bags: SafeContents = vec![cert_bag(certificate_der, Some(attributes))?];
+
+	for certificate in options.chain.iter() {
+		cert_bags.push(cert_bag(certificate.to_der()?, None)?);
+	}
+
+	let authenticated_safe = vec![data_content(&cert_bags)?, data_content(&key_bags)?].to_der()?;
+
+	let mut mac_salt = [0u8; 16];
+	context.rng().fill_bytes(&mut mac_salt);
+
+	let mac_key = derive_key_utf8::<Sha256>(
+		&options.password,
+		&mac_salt,
+		Pkcs12KeyType::Mac,
+		ITERATIONS as i32,
+		Sha256::output_size(),
+	)?;
+
+	let mut mac = Hmac::<Sha256>::new_from_slice(&mac_key)?;
+	mac.update(&authenticated_safe);
+
+	let pfx = Pfx {
+		version: Version::V3,
+		auth_safe: ContentInfo {
+			content_type: rfc5911::ID_DATA,
+			content: Any::encode_from(&OctetString::new(authenticated_safe)?)?,
+		},
+		mac_data: Some(MacData {
+			mac: DigestInfo {
+				algorithm: AlgorithmIdentifierOwned {
+					oid: rfc5912::ID_SHA_256,
+					parameters: Some(Any::null()),
+				},
+				digest: OctetString::new(mac.finalize().into_bytes().as_slice())?,
+			},
+			mac_salt: OctetString::new(mac_salt)?,
+			iterations: ITERATIONS as i32,
+		}),
+	};
+
+	Ok(pfx.to_der()?)
+}
+
+fn attribute(oid: ObjectIdentifier, value: Any) -> Result<Attribute> {
+	Ok(Attribute {
+		oid,
+		values: SetOfVec::try_from([value])?,
+	})
+}
+
+fn cert_bag(certificate_der: Vec<u8>, attributes: Option<Attributes>) -> Result<SafeBag> {
+	Ok(SafeBag {
+		bag_id: PKCS_12_CERT_BAG_OID,
+		bag_value: CertBag {
+			cert_id: PKCS_12_X509_CERT_OID,
+			cert_value: OctetString::new(certificate_der)?,
+		}
+		.to_der()?,
+		bag_attributes: attributes,
+	})
+}
+
+fn data_content(safe_contents: &SafeContents) -> Result<ContentInfo> {
+	Ok(ContentInfo {
+		content_type: rfc5911::ID_DATA,
+		content: Any::encode_from(&OctetString::new(safe_contents.to_der()?)?)?,
+	})
+}
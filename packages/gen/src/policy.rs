@@ -0,0 +1,171 @@
+use const_oid::db::rfc5280::{ID_CE_INHIBIT_ANY_POLICY, ID_QT_CPS, ID_QT_UNOTICE};
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use der::asn1::{Ia5String, Ia5StringRef};
+use der::{Any, EncodeValue, FixedTag, Length, Tag, Writer};
+use x509_cert::ext::pkix::certpolicy::{
+	DisplayText, PolicyInformation, PolicyQualifierInfo, UserNotice,
+};
+use x509_cert::ext::pkix::{CertificatePolicies, PolicyConstraints, PolicyMapping, PolicyMappings};
+use x509_cert::ext::Extension;
+
+use common::*;
+
+use super::certificate::ToExtension;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Policy {
+	pub oid: ObjectIdentifier,
+	pub cps_uri: Option<String>,
+	pub user_notice: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CaPolicy {
+	pub mappings: Vec<(ObjectIdentifier, ObjectIdentifier)>,
+	pub require_explicit_policy: Option<u32>,
+	pub inhibit_policy_mapping: Option<u32>,
+	pub inhibit_any_policy: Option<u32>,
+}
+
+struct InhibitAnyPolicy(u32);
+
+impl AssociatedOid for InhibitAnyPolicy {
+	const OID: ObjectIdentifier = ID_CE_INHIBIT_ANY_POLICY;
+}
+
+impl FixedTag for InhibitAnyPolicy {
+	const TAG: Tag = Tag::Integer;
+}
+
+impl EncodeValue for InhibitAnyPolicy {
+	fn value_len(&self) -> der::Result<Length> {
+		self.0.value_len()
+	}
+
+	fn encode_value(&self, writer: &mut impl Writer) -> der::Result<()> {
+		self.0.encode_value(writer)
+	}
+}
+
+impl TryFrom<Policy> for PolicyInformation {
+	type Error = Error;
+
+	fn try_from(value: Policy) -> Result<PolicyInformation, Self::Error> {
+		let mut qualifiers = Vec::new();
+
+		if let Some(cps_uri) = value.cps_uri {
+			qualifiers.push(PolicyQualifierInfo {
+				policy_qualifier_id: ID_QT_CPS,
+				qualifier: Some(Any::encode_from(&Ia5String::new(&cps_uri)?)?),
+			});
+		}
+
+		if let Some(user_notice) = value.user_notice {
+			qualifiers.push(PolicyQualifierInfo {
+				policy_qualifier_id: ID_QT_UNOTICE,
+				qualifier: Some(Any::encode_from(&UserNotice {
+					notice_ref: None,
+					explicit_text: Some(DisplayText::Utf8String(user_notice)),
+				})?),
+			});
+		}
+
+		Ok(PolicyInformation {
+			policy_identifier: value.oid,
+			policy_qualifiers: (!qualifiers.is_empty()).then_some(qualifiers),
+		})
+	}
+}
+
+impl TryFrom<PolicyInformation> for Policy {
+	type Error = Error;
+
+	fn try_from(value: PolicyInformation) -> Result<Policy, Self::Error> {
+		let mut policy = Policy {
+			oid: value.policy_identifier,
+			cps_uri: None,
+			user_notice: None,
+		};
+
+		for qualifier in value.policy_qualifiers.into_iter().flatten() {
+			let content = qualifier
+				.qualifier
+				.ok_or_else(|| Error::msg("policy qualifier has no value"))?;
+
+			match qualifier.policy_qualifier_id {
+				ID_QT_CPS if policy.cps_uri.is_none() => {
+					policy.cps_uri = Some(content.decode_as::<Ia5StringRef>()?.to_string());
+				}
+				ID_QT_UNOTICE if policy.user_notice.is_none() => {
+					policy.user_notice = match content.decode_as::<UserNotice>()?.explicit_text {
+						Some(DisplayText::Utf8String(text)) => Some(text),
+						Some(DisplayText::Ia5String(text)) => Some(text.to_string()),
+						None => None,
+					};
+				}
+				oid => {
+					return Err(Error::msg(format!("unsupported policy qualifier {oid}")));
+				}
+			}
+		}
+
+		Ok(policy)
+	}
+}
+
+pub(crate) fn policy_extensions(
+	policies: Vec<Policy>,
+	ca_policy: Option<CaPolicy>,
+) -> Result<Vec<Extension>> {
+	let mut extensions = Vec::new();
+
+	if !policies.is_empty() {
+		extensions.push(
+			CertificatePolicies(
+				policies
+					.into_iter()
+					.map(TryFrom::try_from)
+					.collect::<Result<_>>()?,
+			)
+			.to_extension(false)?,
+		);
+	}
+
+	let Some(ca_policy) = ca_policy else {
+		return Ok(extensions);
+	};
+
+	if !ca_policy.mappings.is_empty() {
+		extensions.push(
+			PolicyMappings(
+				ca_policy
+					.mappings
+					.into_iter()
+					.map(
+						|(issuer_domain_policy, subject_domain_policy)| PolicyMapping {
+							issuer_domain_policy,
+							subject_domain_policy,
+						},
+					)
+					.collect(),
+			)
+			.to_extension(true)?,
+		);
+	}
+
+	if ca_policy.require_explicit_policy.is_some() || ca_policy.inhibit_policy_mapping.is_some() {
+		extensions.push(
+			PolicyConstraints {
+				require_explicit_policy: ca_policy.require_explicit_policy,
+				inhibit_policy_mapping: ca_policy.inhibit_policy_mapping,
+			}
+			.to_extension(true)?,
+		);
+	}
+
+	if let Some(skip_certs) = ca_policy.inhibit_any_policy {
+		extensions.push(InhibitAnyPolicy(skip_certs).to_extension(true)?);
+	}
+
+	Ok(extensions)
+}
@@ -0,0 +1,553 @@
+use std::fmt::{self, Debug};
+use std::iter::{Product, Sum};
+use std::ops::{Add, AddAssign, Mul, MulAssign, Neg, ShrAssign, Sub, SubAssign};
+
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use elliptic_curve::bigint::modular::constant_mod::Residue;
+use elliptic_curve::bigint::{impl_modulus, ArrayEncoding, Integer, U256, U384, U512};
+use elliptic_curve::consts::{U32, U48, U64};
+use elliptic_curve::ff::helpers::{sqrt_ratio_generic, sqrt_tonelli_shanks};
+use elliptic_curve::ff::{Field, PrimeField};
+use elliptic_curve::generic_array::typenum::Unsigned;
+use elliptic_curve::ops::{Invert, Reduce};
+use elliptic_curve::point::{AffineCoordinates, DecompressPoint};
+use elliptic_curve::rand_core::RngCore;
+use elliptic_curve::scalar::{FromUintUnchecked, IsHigh};
+use elliptic_curve::subtle::{
+	Choice, ConditionallySelectable, ConstantTimeEq, ConstantTimeGreater, CtOption,
+};
+use elliptic_curve::zeroize::DefaultIsZeroes;
+use elliptic_curve::{
+	Curve, CurveArithmetic, FieldBytes, FieldBytesEncoding, PrimeCurve, PublicKey, ScalarPrimitive,
+};
+use primeorder::point_arithmetic::EquationAIsGeneric;
+use primeorder::{AffinePoint, PrimeCurveParams, ProjectivePoint};
+
+use common::*;
+
+macro_rules! impl_field_element {
+	(
+		$name:ident,
+		$params:ident,
+		$curve:ty,
+		$uint:ty,
+		$modulus:literal,
+		$generator:literal,
+		$s:literal,
+		$root_of_unity:literal,
+		$delta:literal
+	) => {
+		impl_modulus!($params, $uint, $modulus);
+
+		#[derive(Clone, Copy, PartialEq, Eq)]
+		pub struct $name(Residue<$params, { <$uint>::LIMBS }>);
+
+		impl $name {
+			const MODULUS_UINT: $uint = <$uint>::from_be_hex($modulus);
+
+			const fn from_u64(value: u64) -> Self {
+				Self(Residue::new(&<$uint>::from_u64(value)))
+			}
+
+			const fn from_hex(hex: &str) -> Self {
+				Self(Residue::new(&<$uint>::from_be_hex(hex)))
+			}
+
+			fn from_uint(uint: $uint) -> CtOption<Self> {
+				Residue::new_checked(&uint).map(Self)
+			}
+
+			fn to_uint(self) -> $uint {
+				self.0.retrieve()
+			}
+		}
+
+		impl Field for $name {
+			const ZERO: Self = Self(Residue::ZERO);
+			const ONE: Self = Self(Residue::ONE);
+
+			fn random(mut rng: impl RngCore) -> Self {
+				let mut bytes = FieldBytes::<$curve>::default();
+
+				loop {
+					rng.fill_bytes(&mut bytes);
+
+					if let Some(value) = Self::from_repr(bytes).into() {
+						return value;
+					}
+				}
+			}
+
+			fn square(&self) -> Self {
+				Self(self.0.square())
+			}
+
+			fn double(&self) -> Self {
+				Self(self.0.add(&self.0))
+			}
+
+			fn invert(&self) -> CtOption<Self> {
+				let (inverse, is_some) = self.0.invert();
+
+				CtOption::new(Self(inverse), is_some.into())
+			}
+
+			fn sqrt(&self) -> CtOption<Self> {
+				sqrt_tonelli_shanks(self, u64_words(&Self::MODULUS_UINT.shr_vartime($s + 1)))
+			}
+
+			fn sqrt_ratio(num: &Self, div: &Self) -> (Choice, Self) {
+				sqrt_ratio_generic(num, div)
+			}
+		}
+
+		impl PrimeField for $name {
+			type Repr = FieldBytes<$curve>;
+
+			const MODULUS: &'static str = $modulus;
+			const NUM_BITS: u32 = <$uint>::BITS as u32;
+			const CAPACITY: u32 = Self::NUM_BITS - 1;
+			const TWO_INV: Self = Self(Self::from_u64(2).0.invert().0);
+			const MULTIPLICATIVE_GENERATOR: Self = Self::from_u64($generator);
+			const S: u32 = $s;
+			const ROOT_OF_UNITY: Self = Self::from_hex($root_of_unity);
+			const ROOT_OF_UNITY_INV: Self = Self(Self::ROOT_OF_UNITY.0.invert().0);
+			const DELTA: Self = Self::from_u64($delta);
+
+			fn from_repr(repr: Self::Repr) -> CtOption<Self> {
+				Self::from_uint(<$uint>::from_be_byte_array(repr))
+			}
+
+			fn to_repr(&self) -> Self::Repr {
+				self.to_uint().to_be_byte_array()
+			}
+
+			fn is_odd(&self) -> Choice {
+				self.to_uint().is_odd()
+			}
+		}
+
+		impl From<u64> for $name {
+			fn from(value: u64) -> Self {
+				Self::from_u64(value)
+			}
+		}
+
+		impl Default for $name {
+			fn default() -> Self {
+				Self::ZERO
+			}
+		}
+
+		impl DefaultIsZeroes for $name {}
+
+		impl Debug for $name {
+			fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+				write!(f, "{}(0x{:X})", stringify!($name), self.to_uint())
+			}
+		}
+
+		impl ConditionallySelectable for $name {
+			fn conditional_select(a: &Self, b: &Self, choice: Choice) -> Self {
+				Self(Residue::conditional_select(&a.0, &b.0, choice))
+			}
+		}
+
+		impl ConstantTimeEq for $name {
+			fn ct_eq(&self, other: &Self) -> Choice {
+				self.0.ct_eq(&other.0)
+			}
+		}
+
+		impl Neg for $name {
+			type Output = Self;
+
+			fn neg(self) -> Self {
+				Self(self.0.neg())
+			}
+		}
+
+		impl_field_op!($name, Add, add, AddAssign, add_assign);
+		impl_field_op!($name, Sub, sub, SubAssign, sub_assign);
+		impl_field_op!($name, Mul, mul, MulAssign, mul_assign);
+
+		impl Sum for $name {
+			fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+				iter.fold(Self::ZERO, Add::add)
+			}
+		}
+
+		impl<'a> Sum<&'a $name> for $name {
+			fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+				iter.copied().sum()
+			}
+		}
+
+		impl Product for $name {
+			fn product<I: Iterator<Item = Self>>(iter: I) -> Self {
+				iter.fold(Self::ONE, Mul::mul)
+			}
+		}
+
+		impl<'a> Product<&'a $name> for $name {
+			fn product<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+				iter.copied().product()
+			}
+		}
+	};
+}
+
+macro_rules! impl_field_op {
+	($name:ident, $op:ident, $method:ident, $assign_op:ident, $assign_method:ident) => {
+		impl $op for $name {
+			type Output = Self;
+
+			fn $method(self, rhs: Self) -> Self {
+				Self(self.0.$method(&rhs.0))
+			}
+		}
+
+		impl $op<&$name> for $name {
+			type Output = Self;
+
+			fn $method(self, rhs: &Self) -> Self {
+				Self(self.0.$method(&rhs.0))
+			}
+		}
+
+		impl $assign_op for $name {
+			fn $assign_method(&mut self, rhs: Self) {
+				*self = $op::$method(*self, rhs);
+			}
+		}
+
+		impl $assign_op<&$name> for $name {
+			fn $assign_method(&mut self, rhs: &Self) {
+				*self = $op::$method(*self, rhs);
+			}
+		}
+	};
+}
+
+macro_rules! impl_scalar {
+	($name:ident, $curve:ty, $uint:ty) => {
+		impl AsRef<$name> for $name {
+			fn as_ref(&self) -> &Self {
+				self
+			}
+		}
+
+		impl PartialOrd for $name {
+			fn partial_cmp(&self, other: &Self) -> Option<std::cmp::Ordering> {
+				Some(self.to_uint().cmp(&other.to_uint()))
+			}
+		}
+
+		impl From<ScalarPrimitive<$curve>> for $name {
+			fn from(scalar: ScalarPrimitive<$curve>) -> Self {
+				Self(Residue::new(scalar.as_uint()))
+			}
+		}
+
+		impl From<$name> for ScalarPrimitive<$curve> {
+			fn from(scalar: $name) -> Self {
+				ScalarPrimitive::new(scalar.to_uint()).unwrap()
+			}
+		}
+
+		impl From<$name> for FieldBytes<$curve> {
+			fn from(scalar: $name) -> Self {
+				scalar.to_repr()
+			}
+		}
+
+		impl From<$name> for $uint {
+			fn from(scalar: $name) -> Self {
+				scalar.to_uint()
+			}
+		}
+
+		impl FromUintUnchecked for $name {
+			type Uint = $uint;
+
+			fn from_uint_unchecked(uint: $uint) -> Self {
+				Self(Residue::new(&uint))
+			}
+		}
+
+		impl Invert for $name {
+			type Output = CtOption<Self>;
+
+			fn invert(&self) -> CtOption<Self> {
+				Field::invert(self)
+			}
+		}
+
+		impl IsHigh for $name {
+			fn is_high(&self) -> Choice {
+				self.to_uint().ct_gt(&Self::MODULUS_UINT.shr_vartime(1))
+			}
+		}
+
+		impl Reduce<$uint> for $name {
+			type Bytes = FieldBytes<$curve>;
+
+			fn reduce(uint: $uint) -> Self {
+				Self(Residue::new(&uint))
+			}
+
+			fn reduce_bytes(bytes: &Self::Bytes) -> Self {
+				Self::reduce(<$uint>::from_be_byte_array(*bytes))
+			}
+		}
+
+		impl ShrAssign<usize> for $name {
+			fn shr_assign(&mut self, rhs: usize) {
+				*self = Self(Residue::new(&self.to_uint().shr_vartime(rhs)));
+			}
+		}
+	};
+}
+
+macro_rules! impl_curve {
+	(
+		$curve:ident,
+		$oid:literal,
+		$uint:ty,
+		$field_bytes_size:ty,
+		$field_element:ident,
+		$scalar:ident,
+		$a:literal,
+		$b:literal,
+		$gx:literal,
+		$gy:literal
+	) => {
+		#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
+		pub struct $curve;
+
+		impl Curve for $curve {
+			type FieldBytesSize = $field_bytes_size;
+			type Uint = $uint;
+
+			const ORDER: $uint = $scalar::MODULUS_UINT;
+		}
+
+		impl PrimeCurve for $curve {}
+
+		impl AssociatedOid for $curve {
+			const OID: ObjectIdentifier = ObjectIdentifier::new_unwrap($oid);
+		}
+
+		impl FieldBytesEncoding<$curve> for $uint {}
+
+		impl CurveArithmetic for $curve {
+			type AffinePoint = AffinePoint<Self>;
+			type ProjectivePoint = ProjectivePoint<Self>;
+			type Scalar = $scalar;
+		}
+
+		impl PrimeCurveParams for $curve {
+			type FieldElement = $field_element;
+			type PointArithmetic = EquationAIsGeneric;
+
+			const EQUATION_A: $field_element = $field_element::from_hex($a);
+			const EQUATION_B: $field_element = $field_element::from_hex($b);
+			const GENERATOR: ($field_element, $field_element) =
+				($field_element::from_hex($gx), $field_element::from_hex($gy));
+		}
+	};
+}
+
+impl_field_element!(
+	FieldElement256,
+	FieldModulus256,
+	BrainpoolP256r1,
+	U256,
+	"a9fb57dba1eea9bc3e660a909d838d726e3bf623d52620282013481d1f6e5377",
+	11,
+	1,
+	"a9fb57dba1eea9bc3e660a909d838d726e3bf623d52620282013481d1f6e5376",
+	121
+);
+
+impl_field_element!(
+	Scalar256,
+	ScalarModulus256,
+	BrainpoolP256r1,
+	U256,
+	"a9fb57dba1eea9bc3e660a909d838d718c397aa3b561a6f7901e0e82974856a7",
+	3,
+	1,
+	"a9fb57dba1eea9bc3e660a909d838d718c397aa3b561a6f7901e0e82974856a6",
+	9
+);
+
+impl_scalar!(Scalar256, BrainpoolP256r1, U256);
+
+impl_curve!(
+	BrainpoolP256r1,
+	"1.3.36.3.3.2.8.1.1.7",
+	U256,
+	U32,
+	FieldElement256,
+	Scalar256,
+	"7d5a0975fc2c3057eef67530417affe7fb8055c126dc5c6ce94a4b44f330b5d9",
+	"26dc5c6ce94a4b44f330b5d9bbd77cbf958416295cf7e1ce6bccdc18ff8c07b6",
+	"8bd2aeb9cb7e57cb2c4b482ffc81b7afb9de27e1e3bd23c23a4453bd9ace3262",
+	"547ef835c3dac4fd97f8461a14611dc9c27745132ded8e545c1d54c72f046997"
+);
+
+impl_field_element!(
+	FieldElement384,
+	FieldModulus384,
+	BrainpoolP384r1,
+	U384,
+	"8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b412b1da197fb71123acd3a729901d1a71874700133107ec53",
+	2,
+	1,
+	"8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b412b1da197fb71123acd3a729901d1a71874700133107ec52",
+	4
+);
+
+impl_field_element!(
+	Scalar384,
+	ScalarModulus384,
+	BrainpoolP384r1,
+	U384,
+	"8cb91e82a3386d280f5d6f7e50e641df152f7109ed5456b31f166e6cac0425a7cf3ab6af6b7fc3103b883202e9046565",
+	2,
+	2,
+	"76cdc6369fb54dde55a851fce47cc5f830bb074c85684b3ee476be128dc50cfa8602aeecf53a1982fcf3b95f8d4258ff",
+	16
+);
+
+impl_scalar!(Scalar384, BrainpoolP384r1, U384);
+
+impl_curve!(
+	BrainpoolP384r1,
+	"1.3.36.3.3.2.8.1.1.11",
+	U384,
+	U48,
+	FieldElement384,
+	Scalar384,
+	"7bc382c63d8c150c3c72080ace05afa0c2bea28e4fb22787139165efba91f90f8aa5814a503ad4eb04a8c7dd22ce2826",
+	"04a8c7dd22ce28268b39b55416f0447c2fb77de107dcd2a62e880ea53eeb62d57cb4390295dbc9943ab78696fa504c11",
+	"1d1c64f068cf45ffa2a63a81b7c13f6b8847a3e77ef14fe3db7fcafe0cbd10e8e826e03436d646aaef87b2e247d4af1e",
+	"8abe1d7520f9c2a45cb1eb8e95cfd55262b70b29feec5864e19c054ff99129280e4646217791811142820341263c5315"
+);
+
+impl_field_element!(
+	FieldElement512,
+	FieldModulus512,
+	BrainpoolP512r1,
+	U512,
+	"aadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca703308717d4d9b009bc66842aecda12ae6a380e62881ff2f2d82c68528aa6056583a48f3",
+	2,
+	1,
+	"aadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca703308717d4d9b009bc66842aecda12ae6a380e62881ff2f2d82c68528aa6056583a48f2",
+	4
+);
+
+impl_field_element!(
+	Scalar512,
+	ScalarModulus512,
+	BrainpoolP512r1,
+	U512,
+	"aadd9db8dbe9c48b3fd4e6ae33c9fc07cb308db3b3c9d20ed6639cca70330870553e5c414ca92619418661197fac10471db1d381085ddaddb58796829ca90069",
+	7,
+	3,
+	"73f4a3dac6cabf594783bead7df20bb1713b6e3c45ccfe628590e1866f006103a70a67e4093ee5838f3d67a1794f1b7c7a97f496cab905079be4c815611ab592",
+	5764801
+);
+
+impl_scalar!(Scalar512, BrainpoolP512r1, U512);
+
+impl_curve!(
+	BrainpoolP512r1,
+	"1.3.36.3.3.2.8.1.1.13",
+	U512,
+	U64,
+	FieldElement512,
+	Scalar512,
+	"7830a3318b603b89e2327145ac234cc594cbdd8d3df91610a83441caea9863bc2ded5d5aa8253aa10a2ef1c98b9ac8b57f1117a72bf2c7b9e7c1ac4d77fc94ca",
+	"3df91610a83441caea9863bc2ded5d5aa8253aa10a2ef1c98b9ac8b57f1117a72bf2c7b9e7c1ac4d77fc94cadc083e67984050b75ebae5dd2809bd638016f723",
+	"81aee4bdd82ed9645a21322e9c4c6a9385ed9f70b5d916c1b43b62eef4d0098eff3b1f78e2d0d48d50d1687b93b97d5f7c6d5047406a5e688b352209bcb9f822",
+	"7dde385d566332ecc0eabfa9cf7822fdf209f70024a57b1aa000c55b881f8111b2dcde494a5f485e5bca4bd88a2763aed1ca2b2fa8f0540678cd1e0f3ad80892"
+);
+
+pub(super) fn encode_point<C: PrimeCurveParams>(
+	public_key: &PublicKey<C>,
+	compress: bool,
+) -> Vec<u8> {
+	let point = public_key.as_affine();
+	let x = point.x();
+
+	if compress {
+		return [&[0x02 | point.y_is_odd().unwrap_u8()], x.as_slice()].concat();
+	}
+
+	let x = C::FieldElement::from_repr(x).unwrap();
+	let y = y_coordinate::<C>(&x, point.y_is_odd()).unwrap();
+
+	[&[0x04], x.to_repr().as_slice(), y.to_repr().as_slice()].concat()
+}
+
+pub(super) fn decode_point<C>(bytes: &[u8]) -> Result<PublicKey<C>>
+where
+	C: PrimeCurveParams,
+	FieldBytes<C>: Copy,
+{
+	let invalid = || Error::msg("invalid elliptic curve point");
+
+	let size = C::FieldBytesSize::USIZE;
+
+	let (tag, coordinates) = bytes.split_first().ok_or_else(invalid)?;
+
+	let (x, y) = match tag {
+		0x02 | 0x03 if coordinates.len() == size => (coordinates, None),
+		0x04 if coordinates.len() == 2 * size => {
+			let (x, y) = coordinates.split_at(size);
+
+			(x, Some(y))
+		}
+		_ => return Err(invalid()),
+	};
+
+	let y_is_odd = Choice::from(y.map_or(tag & 1, |y| y[size - 1] & 1));
+
+	let x = Option::<C::FieldElement>::from(C::FieldElement::from_repr(
+		FieldBytes::<C>::clone_from_slice(x),
+	))
+	.ok_or_else(invalid)?;
+
+	let expected_y =
+		Option::<C::FieldElement>::from(y_coordinate::<C>(&x, y_is_odd)).ok_or_else(invalid)?;
+
+	if y.is_some_and(|y| y != expected_y.to_repr().as_slice()) {
+		return Err(invalid());
+	}
+
+	let point =
+		Option::<AffinePoint<C>>::from(AffinePoint::<C>::decompress(&x.to_repr(), y_is_odd))
+			.ok_or_else(invalid)?;
+
+	Ok(PublicKey::from_affine(point)?)
+}
+
+fn y_coordinate<C: PrimeCurveParams>(
+	x: &C::FieldElement,
+	y_is_odd: Choice,
+) -> CtOption<C::FieldElement> {
+	let alpha = *x * x * x + C::EQUATION_A * x + C::EQUATION_B;
+
+	alpha.sqrt().map(|beta| {
+		C::FieldElement::conditional_select(&-beta, &beta, beta.is_odd().ct_eq(&y_is_odd))
+	})
+}
+
+fn u64_words<T: ArrayEncoding>(uint: &T) -> Vec<u64> {
+	uint.to_le_byte_array()
+		.chunks(8)
+		.map(|chunk| u64::from_le_bytes(chunk.try_into().unwrap()))
+		.collect()
+}
@@ -1,15 +1,16 @@
-use der::{AnyRef, Decode};
+use der::{AnyRef, Decode, Encode};
 use ed25519_dalek::pkcs8::{KeypairBytes, ALGORITHM_ID};
-use ed25519_dalek::{Signer, SigningKey};
-use pkcs8::{EncodePrivateKey, LineEnding};
+use ed25519_dalek::{Signature, Signer, SigningKey, Verifier, VerifyingKey};
+use pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding, SecretDocument};
+use rand_core::CryptoRngCore;
 use spki::{
-	AlgorithmIdentifier, AlgorithmIdentifierRef, AssociatedAlgorithmIdentifier, EncodePublicKey,
-	SignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned,
+	AlgorithmIdentifier, AlgorithmIdentifierRef, AssociatedAlgorithmIdentifier, DecodePublicKey,
+	EncodePublicKey, SignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned,
 };
 
 use common::*;
 
-use super::{PrivateKey, SignatureStrategy};
+use super::{new_scheme, KeyPair, PrivateKey, SignatureStrategy};
 
 #[derive(Default)]
 pub struct Ed25519;
@@ -27,9 +28,10 @@ impl SignatureAlgorithmIdentifier for Ed25519 {
 }
 
 impl SignatureStrategy for Ed25519 {
-	fn generate_key(&self) -> Result<Box<dyn PrivateKey>> {
-		let mut rng = rand::thread_rng();
-
+	fn generate_key_with_rng(
+		&self,
+		mut rng: &mut dyn CryptoRngCore,
+	) -> Result<Box<dyn PrivateKey>> {
 		Ok(Box::new(Ed25519Key(SigningKey::generate(&mut rng))))
 	}
 }
@@ -54,7 +56,33 @@ impl PrivateKey for Ed25519Key {
 		Ok(keypair_bytes.to_pkcs8_pem(line_ending)?.to_string())
 	}
 
-	fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+	fn to_der(&self) -> Result<SecretDocument> {
+		let keypair_bytes = KeypairBytes {
+			secret_key: self.0.to_bytes(),
+			public_key: None,
+		};
+
+		Ok(keypair_bytes.to_pkcs8_der()?)
+	}
+
+	fn sign_with_rng(&self, data: &[u8], _rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>> {
 		Ok(self.0.try_sign(data)?.to_vec())
 	}
 }
+
+pub(super) fn load_pkcs8(der: &[u8]) -> Result<KeyPair> {
+	Ok((
+		new_scheme::<Ed25519>(),
+		Box::new(Ed25519Key(SigningKey::from_pkcs8_der(der)?)),
+	))
+}
+
+pub(super) fn verify(
+	public_key: &SubjectPublicKeyInfoOwned,
+	data: &[u8],
+	signature: &[u8],
+) -> Result<()> {
+	let public_key = VerifyingKey::from_public_key_der(&public_key.to_der()?)?;
+
+	Ok(public_key.verify(data, &Signature::from_slice(signature)?)?)
+}
@@ -1,29 +1,172 @@
+pub mod brainpool;
 pub mod ecdsa;
 pub mod ed25519;
 pub mod rsa;
+pub mod rsa_pss;
 
 use std::rc::Rc;
 
-use pkcs8::LineEnding;
+use const_oid::db::{rfc5912, rfc8410};
+use der::pem::PemLabel;
+use der::Decode;
+use enum_iterator::Sequence;
+use parse_display::Display;
+use pkcs5::pbes2;
+use pkcs8::{EncryptedPrivateKeyInfo, LineEnding, PrivateKeyInfo, SecretDocument};
+use rand_core::CryptoRngCore;
+use sha2::{Sha256, Sha384, Sha512};
 use spki::{
-	DynAssociatedAlgorithmIdentifier, DynSignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned,
+	AlgorithmIdentifierOwned, DynAssociatedAlgorithmIdentifier, DynSignatureAlgorithmIdentifier,
+	SubjectPublicKeyInfoOwned,
 };
 
 use common::*;
 
+const PBKDF2_ITERATIONS: u32 = 100_000;
+
 pub trait SignatureStrategy:
 	DynAssociatedAlgorithmIdentifier + DynSignatureAlgorithmIdentifier
 {
-	fn generate_key(&self) -> Result<Box<dyn PrivateKey>>;
+	fn generate_key(&self) -> Result<Box<dyn PrivateKey>> {
+		self.generate_key_with_rng(&mut rand::thread_rng())
+	}
+
+	fn generate_key_with_rng(&self, rng: &mut dyn CryptoRngCore) -> Result<Box<dyn PrivateKey>>;
 }
 
 pub trait PrivateKey {
 	fn to_subject_public_key_info(&self) -> Result<SubjectPublicKeyInfoOwned>;
 	fn to_pem(&self, line_ending: LineEnding) -> Result<String>;
+	fn to_der(&self) -> Result<SecretDocument>;
+
+	fn to_encrypted_pem(&self, password: &str, line_ending: LineEnding) -> Result<String> {
+		Ok(self
+			.to_encrypted_der(password)?
+			.to_pem(EncryptedPrivateKeyInfo::PEM_LABEL, line_ending)?
+			.to_string())
+	}
+
+	fn to_encrypted_der(&self, password: &str) -> Result<SecretDocument> {
+		self.to_encrypted_der_with_rng(password, &mut rand::thread_rng())
+	}
+
+	fn to_encrypted_der_with_rng(
+		&self,
+		password: &str,
+		rng: &mut dyn CryptoRngCore,
+	) -> Result<SecretDocument> {
+		let mut salt = [0u8; 16];
+		let mut iv = [0u8; 16];
+		rng.fill_bytes(&mut salt);
+		rng.fill_bytes(&mut iv);
+
+		let parameters = pbes2::Parameters::pbkdf2_sha256_aes256cbc(PBKDF2_ITERATIONS, &salt, &iv)
+			.map_err(pkcs8::Error::from)?;
+
+		Ok(PrivateKeyInfo::try_from(self.to_der()?.as_bytes())?
+			.encrypt_with_params(parameters, password)?)
+	}
 
-	fn sign(&self, data: &[u8]) -> Result<Vec<u8>>;
+	fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
+		self.sign_with_rng(data, &mut rand::thread_rng())
+	}
+
+	fn sign_with_rng(&self, data: &[u8], rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>>;
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Display, Sequence)]
+pub enum DigestAlgorithm {
+	#[default]
+	#[display("SHA-256")]
+	Sha256,
+	#[display("SHA-384")]
+	Sha384,
+	#[display("SHA-512")]
+	Sha512,
 }
 
+pub type KeyPair = (Rc<dyn SignatureStrategy>, Box<dyn PrivateKey>);
+
 pub fn new_scheme<S: SignatureStrategy + Default + 'static>() -> Rc<dyn SignatureStrategy> {
 	Rc::new(S::default())
 }
+
+pub fn verify_signature(
+	algorithm: &AlgorithmIdentifierOwned,
+	public_key: &SubjectPublicKeyInfoOwned,
+	data: &[u8],
+	signature: &[u8],
+) -> Result<()> {
+	match algorithm.oid {
+		rfc5912::SHA_256_WITH_RSA_ENCRYPTION => rsa::verify::<Sha256>(public_key, data, signature),
+		rfc5912::SHA_384_WITH_RSA_ENCRYPTION => rsa::verify::<Sha384>(public_key, data, signature),
+		rfc5912::SHA_512_WITH_RSA_ENCRYPTION => rsa::verify::<Sha512>(public_key, data, signature),
+		rfc5912::ID_RSASSA_PSS => rsa_pss::verify(algorithm, public_key, data, signature),
+		rfc5912::ECDSA_WITH_SHA_256 => ecdsa::verify::<Sha256>(public_key, data, signature),
+		rfc5912::ECDSA_WITH_SHA_384 => ecdsa::verify::<Sha384>(public_key, data, signature),
+		rfc5912::ECDSA_WITH_SHA_512 => ecdsa::verify::<Sha512>(public_key, data, signature),
+		rfc8410::ID_ED_25519 => ed25519::verify(public_key, data, signature),
+		oid => Err(Error::msg(format!("unsupported signature algorithm {oid}"))),
+	}
+}
+
+pub(crate) fn public_keys_match(
+	a: &SubjectPublicKeyInfoOwned,
+	b: &SubjectPublicKeyInfoOwned,
+) -> Result<bool> {
+	if a.algorithm.oid != b.algorithm.oid {
+		return Ok(false);
+	}
+
+	match a.algorithm.oid {
+		rfc5912::ID_EC_PUBLIC_KEY => Ok(ecdsa::curve(a)? == ecdsa::curve(b)?
+			&& ecdsa::uncompressed_point(a)? == ecdsa::uncompressed_point(b)?),
+		rfc5912::RSA_ENCRYPTION | rfc5912::ID_RSASSA_PSS => {
+			Ok(rsa_pss::public_key(a)? == rsa_pss::public_key(b)?)
+		}
+		_ => Ok(a.subject_public_key == b.subject_public_key),
+	}
+}
+
+pub fn load_key(data: &[u8]) -> Result<KeyPair> {
+	load_key_with_digest(data, DigestAlgorithm::default())
+}
+
+pub fn load_key_with_digest(data: &[u8], digest: DigestAlgorithm) -> Result<KeyPair> {
+	if !data.starts_with(b"-----BEGIN") {
+		if PrivateKeyInfo::from_der(data).is_ok() {
+			return load_pkcs8(data, digest);
+		}
+
+		if pkcs1::RsaPrivateKey::from_der(data).is_ok() {
+			return rsa::load_pkcs1(data, digest);
+		}
+
+		if sec1::EcPrivateKey::from_der(data).is_ok() {
+			return ecdsa::load_sec1(data);
+		}
+
+		return Err(Error::msg("unrecognized private key format"));
+	}
+
+	let (label, der) = der::pem::decode_vec(data).map_err(der::Error::from)?;
+
+	match label {
+		"PRIVATE KEY" => load_pkcs8(&der, digest),
+		"RSA PRIVATE KEY" => rsa::load_pkcs1(&der, digest),
+		"EC PRIVATE KEY" => ecdsa::load_sec1(&der),
+		label => Err(Error::msg(format!("unsupported PEM label {label}"))),
+	}
+}
+
+fn load_pkcs8(der: &[u8], digest: DigestAlgorithm) -> Result<KeyPair> {
+	let algorithm = PrivateKeyInfo::from_der(der)?.algorithm;
+
+	match algorithm.oid {
+		rfc5912::RSA_ENCRYPTION => rsa::load_pkcs8(der, digest),
+		rfc5912::ID_RSASSA_PSS => rsa_pss::load_pkcs8(der, digest),
+		rfc5912::ID_EC_PUBLIC_KEY => ecdsa::load_pkcs8(algorithm.parameters_oid()?, der),
+		rfc8410::ID_ED_25519 => ed25519::load_pkcs8(der),
+		oid => Err(Error::msg(format!("unsupported key algorithm {oid}"))),
+	}
+}
@@ -1,12 +1,17 @@
 use std::marker::PhantomData;
+use std::rc::Rc;
 
+use const_oid::db::rfc5912;
 use const_oid::AssociatedOid;
 use der::Decode;
-use pkcs8::{EncodePrivateKey, LineEnding};
-use rsa::pkcs1v15::{SigningKey, VerifyingKey};
-use rsa::RsaPublicKey;
+use pkcs1::DecodeRsaPrivateKey;
+use pkcs8::{DecodePrivateKey, EncodePrivateKey, LineEnding, SecretDocument};
+use rand_core::CryptoRngCore;
+use rsa::pkcs1v15::{Signature, SigningKey, VerifyingKey};
+use rsa::traits::PublicKeyParts;
+use rsa::{RsaPrivateKey, RsaPublicKey};
 use sha2::{Digest, Sha256, Sha384, Sha512};
-use signature::{RandomizedSigner, SignatureEncoding};
+use signature::{RandomizedSigner, SignatureEncoding, Verifier};
 use spki::{
 	AlgorithmIdentifier, AssociatedAlgorithmIdentifier, EncodePublicKey,
 	SignatureAlgorithmIdentifier, SubjectPublicKeyInfoOwned,
@@ -14,7 +19,10 @@ use spki::{
 
 use common::*;
 
-use super::{PrivateKey, SignatureStrategy};
+use super::{rsa_pss, DigestAlgorithm, KeyPair, PrivateKey, SignatureStrategy};
+
+const MIN_KEY_SIZE: usize = 2048;
+pub(super) const MAX_KEY_SIZE: usize = 16384;
 
 pub type Rsa2048WithSha256 = Rsa<2048, Sha256>;
 pub type Rsa2048WithSha384 = Rsa<2048, Sha384>;
@@ -51,13 +59,53 @@ impl<const SIZE: usize, D: Digest + AssociatedOid + 'static> SignatureStrategy f
 where
 	SigningKey<D>: SignatureAlgorithmIdentifier,
 {
-	fn generate_key(&self) -> Result<Box<dyn PrivateKey>> {
-		let mut rng = rand::thread_rng();
-
+	fn generate_key_with_rng(
+		&self,
+		mut rng: &mut dyn CryptoRngCore,
+	) -> Result<Box<dyn PrivateKey>> {
 		Ok(Box::new(RsaKey(SigningKey::<D>::random(&mut rng, SIZE)?)))
 	}
 }
 
+pub struct LoadedRsa<D> {
+	bits: usize,
+	digest: PhantomData<D>,
+}
+
+impl<D: Digest + 'static> AssociatedAlgorithmIdentifier for LoadedRsa<D>
+where
+	SigningKey<D>: AssociatedAlgorithmIdentifier,
+{
+	type Params = <SigningKey<D> as AssociatedAlgorithmIdentifier>::Params;
+
+	const ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
+		SigningKey::<D>::ALGORITHM_IDENTIFIER;
+}
+
+impl<D: Digest + 'static> SignatureAlgorithmIdentifier for LoadedRsa<D>
+where
+	SigningKey<D>: SignatureAlgorithmIdentifier,
+{
+	type Params = <SigningKey<D> as SignatureAlgorithmIdentifier>::Params;
+
+	const SIGNATURE_ALGORITHM_IDENTIFIER: AlgorithmIdentifier<Self::Params> =
+		<SigningKey<D> as SignatureAlgorithmIdentifier>::SIGNATURE_ALGORITHM_IDENTIFIER;
+}
+
+impl<D: Digest + AssociatedOid + 'static> SignatureStrategy for LoadedRsa<D>
+where
+	SigningKey<D>: SignatureAlgorithmIdentifier,
+{
+	fn generate_key_with_rng(
+		&self,
+		mut rng: &mut dyn CryptoRngCore,
+	) -> Result<Box<dyn PrivateKey>> {
+		Ok(Box::new(RsaKey(SigningKey::<D>::random(
+			&mut rng, self.bits,
+		)?)))
+	}
+}
+
 struct RsaKey<D: Digest>(SigningKey<D>);
 
 impl<D: Digest + AssociatedOid> PrivateKey for RsaKey<D> {
@@ -73,9 +121,103 @@ impl<D: Digest + AssociatedOid> PrivateKey for RsaKey<D> {
 		Ok(self.0.to_pkcs8_pem(line_ending)?.as_str().to_owned())
 	}
 
-	fn sign(&self, data: &[u8]) -> Result<Vec<u8>> {
-		let mut rng = rand::thread_rng();
+	fn to_der(&self) -> Result<SecretDocument> {
+		Ok(self.0.to_pkcs8_der()?)
+	}
 
+	fn sign_with_rng(&self, data: &[u8], mut rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>> {
 		Ok(self.0.try_sign_with_rng(&mut rng, data)?.to_vec())
 	}
 }
+
+pub(super) fn load_pkcs8(der: &[u8], digest: DigestAlgorithm) -> Result<KeyPair> {
+	load(RsaPrivateKey::from_pkcs8_der(der)?, digest)
+}
+
+pub(super) fn load_pkcs1(der: &[u8], digest: DigestAlgorithm) -> Result<KeyPair> {
+	load(RsaPrivateKey::from_pkcs1_der(der)?, digest)
+}
+
+fn load(private_key: RsaPrivateKey, digest: DigestAlgorithm) -> Result<KeyPair> {
+	let bits = check_key_size(&private_key)?;
+
+	match digest {
+		DigestAlgorithm::Sha256 => Ok(load_with::<Sha256>(private_key, bits)),
+		DigestAlgorithm::Sha384 => Ok(load_with::<Sha384>(private_key, bits)),
+		DigestAlgorithm::Sha512 => Ok(load_with::<Sha512>(private_key, bits)),
+	}
+}
+
+fn load_with<D>(private_key: RsaPrivateKey, bits: usize) -> KeyPair
+where
+	D: Digest + AssociatedOid + 'static,
+	SigningKey<D>: SignatureAlgorithmIdentifier,
+{
+	(
+		Rc::new(LoadedRsa::<D> {
+			bits,
+			digest: PhantomData,
+		}),
+		Box::new(RsaKey(SigningKey::<D>::new(private_key))),
+	)
+}
+
+pub(super) fn check_key_size(private_key: &RsaPrivateKey) -> Result<usize> {
+	match private_key.n().bits() {
+		bits if bits < MIN_KEY_SIZE => Err(Error::msg(format!(
+			"RSA key size {bits} is below the minimum of {MIN_KEY_SIZE} bits"
+		))),
+		bits => Ok(bits),
+	}
+}
+
+pub(super) fn verify<D: Digest + AssociatedOid>(
+	public_key: &SubjectPublicKeyInfoOwned,
+	data: &[u8],
+	signature: &[u8],
+) -> Result<()> {
+	if public_key.algorithm.oid != rfc5912::RSA_ENCRYPTION {
+		return Err(Error::msg(format!(
+			"PKCS#1 v1.5 signatures cannot be verified with a {} key",
+			public_key.algorithm.oid
+		)));
+	}
+
+	let public_key = VerifyingKey::<D>::new(rsa_pss::public_key(public_key)?);
+
+	Ok(public_key.verify(data, &Signature::try_from(signature)?)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use rand_core::OsRng;
+	use spki::AlgorithmIdentifierOwned;
+
+	use super::*;
+
+	#[test]
+	fn pss_keys_reject_pkcs1v15_signatures() {
+		let private_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+
+		let signature = SigningKey::<Sha256>::new(private_key.clone())
+			.sign_with_rng(&mut OsRng, b"data")
+			.to_vec();
+
+		let mut public_key = SubjectPublicKeyInfoOwned::from_der(
+			RsaPublicKey::from(&private_key)
+				.to_public_key_der()
+				.unwrap()
+				.as_bytes(),
+		)
+		.unwrap();
+
+		assert!(verify::<Sha256>(&public_key, b"data", &signature).is_ok());
+
+		public_key.algorithm = AlgorithmIdentifierOwned {
+			oid: rfc5912::ID_RSASSA_PSS,
+			parameters: None,
+		};
+
+		assert!(verify::<Sha256>(&public_key, b"data", &signature).is_err());
+	}
+}
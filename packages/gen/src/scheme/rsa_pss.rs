@@ -0,0 +1,323 @@
+use std::marker::PhantomData;
+use std::rc::Rc;
+
+use ::rsa::pss::{Signature, SigningKey, VerifyingKey};
+use ::rsa::{BigUint, RsaPrivateKey, RsaPublicKey};
+use const_oid::db::rfc5912;
+use const_oid::{AssociatedOid, ObjectIdentifier};
+use der::asn1::BitString;
+use der::referenced::{OwnedToRef, RefToOwned};
+use der::{Any, Decode};
+use pkcs1::{DecodeRsaPrivateKey, EncodeRsaPrivateKey, EncodeRsaPublicKey, RsaPssParams};
+use pkcs8::der::pem::PemLabel;
+use pkcs8::{LineEnding, PrivateKeyInfo, SecretDocument};
+use rand_core::CryptoRngCore;
+use sha2::digest::FixedOutputReset;
+use sha2::{Digest, Sha256, Sha384, Sha512};
+use signature::{RandomizedSigner, SignatureEncoding, Verifier};
+use spki::{
+	AlgorithmIdentifierOwned, DynAssociatedAlgorithmIdentifier, DynSignatureAlgorithmIdentifier,
+	SubjectPublicKeyInfoOwned,
+};
+
+use common::*;
+
+use super::{rsa, DigestAlgorithm, KeyPair, PrivateKey, SignatureStrategy};
+
+pub type Rsa2048PssWithSha256 = RsaPss<2048, Sha256>;
+pub type Rsa2048PssWithSha384 = RsaPss<2048, Sha384>;
+pub type Rsa2048PssWithSha512 = RsaPss<2048, Sha512>;
+
+pub type Rsa4096PssWithSha256 = RsaPss<4096, Sha256>;
+pub type Rsa4096PssWithSha384 = RsaPss<4096, Sha384>;
+pub type Rsa4096PssWithSha512 = RsaPss<4096, Sha512>;
+
+pub type Rsa2048PssRestrictedWithSha256 = RsaPss<2048, Sha256, true>;
+pub type Rsa2048PssRestrictedWithSha384 = RsaPss<2048, Sha384, true>;
+pub type Rsa2048PssRestrictedWithSha512 = RsaPss<2048, Sha512, true>;
+
+pub type Rsa4096PssRestrictedWithSha256 = RsaPss<4096, Sha256, true>;
+pub type Rsa4096PssRestrictedWithSha384 = RsaPss<4096, Sha384, true>;
+pub type Rsa4096PssRestrictedWithSha512 = RsaPss<4096, Sha512, true>;
+
+#[derive(Default)]
+pub struct RsaPss<const SIZE: usize, D, const RESTRICTED: bool = false>(PhantomData<D>);
+
+impl<const SIZE: usize, D, const RESTRICTED: bool> DynAssociatedAlgorithmIdentifier
+	for RsaPss<SIZE, D, RESTRICTED>
+where
+	D: Digest + AssociatedOid,
+{
+	fn algorithm_identifier(&self) -> spki::Result<AlgorithmIdentifierOwned> {
+		key_algorithm_identifier::<D>(RESTRICTED)
+	}
+}
+
+impl<const SIZE: usize, D, const RESTRICTED: bool> DynSignatureAlgorithmIdentifier
+	for RsaPss<SIZE, D, RESTRICTED>
+where
+	D: Digest + AssociatedOid,
+{
+	fn signature_algorithm_identifier(&self) -> spki::Result<AlgorithmIdentifierOwned> {
+		signature_algorithm_identifier::<D>()
+	}
+}
+
+impl<const SIZE: usize, D, const RESTRICTED: bool> SignatureStrategy for RsaPss<SIZE, D, RESTRICTED>
+where
+	D: Digest + AssociatedOid + FixedOutputReset + 'static,
+{
+	fn generate_key_with_rng(
+		&self,
+		mut rng: &mut dyn CryptoRngCore,
+	) -> Result<Box<dyn PrivateKey>> {
+		Ok(Box::new(RsaPssKey {
+			key: SigningKey::<D>::random(&mut rng, SIZE)?,
+			algorithm: key_algorithm_identifier::<D>(RESTRICTED)?,
+		}))
+	}
+}
+
+pub struct LoadedRsaPss<D> {
+	bits: usize,
+	algorithm: AlgorithmIdentifierOwned,
+	digest: PhantomData<D>,
+}
+
+impl<D> DynAssociatedAlgorithmIdentifier for LoadedRsaPss<D>
+where
+	D: Digest + AssociatedOid,
+{
+	fn algorithm_identifier(&self) -> spki::Result<AlgorithmIdentifierOwned> {
+		Ok(self.algorithm.clone())
+	}
+}
+
+impl<D> DynSignatureAlgorithmIdentifier for LoadedRsaPss<D>
+where
+	D: Digest + AssociatedOid,
+{
+	fn signature_algorithm_identifier(&self) -> spki::Result<AlgorithmIdentifierOwned> {
+		signature_algorithm_identifier::<D>()
+	}
+}
+
+impl<D> SignatureStrategy for LoadedRsaPss<D>
+where
+	D: Digest + AssociatedOid + FixedOutputReset + 'static,
+{
+	fn generate_key_with_rng(
+		&self,
+		mut rng: &mut dyn CryptoRngCore,
+	) -> Result<Box<dyn PrivateKey>> {
+		Ok(Box::new(RsaPssKey {
+			key: SigningKey::<D>::random(&mut rng, self.bits)?,
+			algorithm: self.algorithm.clone(),
+		}))
+	}
+}
+
+struct RsaPssKey<D: Digest> {
+	key: SigningKey<D>,
+	algorithm: AlgorithmIdentifierOwned,
+}
+
+impl<D: Digest + AssociatedOid + FixedOutputReset> PrivateKey for RsaPssKey<D> {
+	fn to_subject_public_key_info(&self) -> Result<SubjectPublicKeyInfoOwned> {
+		let public_key = RsaPublicKey::from(self.key.as_ref()).to_pkcs1_der()?;
+
+		Ok(SubjectPublicKeyInfoOwned {
+			algorithm: self.algorithm.clone(),
+			subject_public_key: BitString::from_bytes(public_key.as_bytes())?,
+		})
+	}
+
+	fn to_pem(&self, line_ending: LineEnding) -> Result<String> {
+		Ok(self
+			.to_der()?
+			.to_pem(PrivateKeyInfo::PEM_LABEL, line_ending)?
+			.to_string())
+	}
+
+	fn to_der(&self) -> Result<SecretDocument> {
+		let private_key = self.key.as_ref().to_pkcs1_der()?;
+
+		Ok(SecretDocument::encode_msg(&PrivateKeyInfo::new(
+			self.algorithm.owned_to_ref(),
+			private_key.as_bytes(),
+		))?)
+	}
+
+	fn sign_with_rng(&self, data: &[u8], mut rng: &mut dyn CryptoRngCore) -> Result<Vec<u8>> {
+		Ok(self.key.try_sign_with_rng(&mut rng, data)?.to_vec())
+	}
+}
+
+pub(super) fn load_pkcs8(der: &[u8], digest: DigestAlgorithm) -> Result<KeyPair> {
+	let info = PrivateKeyInfo::from_der(der)?;
+	let private_key = RsaPrivateKey::from_pkcs1_der(info.private_key)?;
+	let algorithm = info.algorithm.ref_to_owned();
+
+	let bits = rsa::check_key_size(&private_key)?;
+
+	let Some(parameters) = &algorithm.parameters else {
+		return match digest {
+			DigestAlgorithm::Sha256 => Ok(load::<Sha256>(private_key, bits, algorithm)),
+			DigestAlgorithm::Sha384 => Ok(load::<Sha384>(private_key, bits, algorithm)),
+			DigestAlgorithm::Sha512 => Ok(load::<Sha512>(private_key, bits, algorithm)),
+		};
+	};
+
+	match digest_oid(&parameters.decode_as::<RsaPssParams>()?)? {
+		Sha256::OID => Ok(load::<Sha256>(private_key, bits, algorithm)),
+		Sha384::OID => Ok(load::<Sha384>(private_key, bits, algorithm)),
+		Sha512::OID => Ok(load::<Sha512>(private_key, bits, algorithm)),
+		oid => Err(Error::msg(format!("unsupported RSASSA-PSS digest {oid}"))),
+	}
+}
+
+fn load<D>(private_key: RsaPrivateKey, bits: usize, algorithm: AlgorithmIdentifierOwned) -> KeyPair
+where
+	D: Digest + AssociatedOid + FixedOutputReset + 'static,
+{
+	(
+		Rc::new(LoadedRsaPss::<D> {
+			bits,
+			algorithm: algorithm.clone(),
+			digest: PhantomData,
+		}),
+		Box::new(RsaPssKey {
+			key: SigningKey::<D>::new(private_key),
+			algorithm,
+		}),
+	)
+}
+
+pub(super) fn verify(
+	algorithm: &AlgorithmIdentifierOwned,
+	public_key: &SubjectPublicKeyInfoOwned,
+	data: &[u8],
+	signature: &[u8],
+) -> Result<()> {
+	let parameters = algorithm
+		.parameters
+		.as_ref()
+		.ok_or_else(|| Error::msg("missing RSASSA-PSS parameters"))?;
+	let parameters = parameters.decode_as::<RsaPssParams>()?;
+
+	let salt_len = usize::from(parameters.salt_len);
+
+	match digest_oid(&parameters)? {
+		Sha256::OID => verify_with::<Sha256>(public_key, salt_len, data, signature),
+		Sha384::OID => verify_with::<Sha384>(public_key, salt_len, data, signature),
+		Sha512::OID => verify_with::<Sha512>(public_key, salt_len, data, signature),
+		oid => Err(Error::msg(format!("unsupported RSASSA-PSS digest {oid}"))),
+	}
+}
+
+pub(crate) fn public_key(public_key: &SubjectPublicKeyInfoOwned) -> Result<RsaPublicKey> {
+	match public_key.algorithm.oid {
+		rfc5912::RSA_ENCRYPTION | rfc5912::ID_RSASSA_PSS => {
+			let public_key =
+				pkcs1::RsaPublicKey::from_der(public_key.subject_public_key.raw_bytes())?;
+
+			Ok(RsaPublicKey::new_with_max_size(
+				BigUint::from_bytes_be(public_key.modulus.as_bytes()),
+				BigUint::from_bytes_be(public_key.public_exponent.as_bytes()),
+				rsa::MAX_KEY_SIZE,
+			)?)
+		}
+		oid => Err(Error::msg(format!("unsupported RSA key algorithm {oid}"))),
+	}
+}
+
+fn verify_with<D: Digest + AssociatedOid + FixedOutputReset>(
+	public_key: &SubjectPublicKeyInfoOwned,
+	salt_len: usize,
+	data: &[u8],
+	signature: &[u8],
+) -> Result<()> {
+	let public_key = VerifyingKey::<D>::new_with_salt_len(self::public_key(public_key)?, salt_len);
+
+	Ok(public_key.verify(data, &Signature::try_from(signature)?)?)
+}
+
+fn digest_oid(parameters: &RsaPssParams) -> Result<ObjectIdentifier> {
+	let mgf_digest = parameters
+		.mask_gen
+		.parameters
+		.as_ref()
+		.map(|digest| digest.oid);
+
+	if parameters.mask_gen.oid != rfc5912::ID_MGF_1 || mgf_digest != Some(parameters.hash.oid) {
+		return Err(Error::msg(
+			"RSASSA-PSS mask generation must use MGF1 with the message digest",
+		));
+	}
+
+	Ok(parameters.hash.oid)
+}
+
+fn signature_algorithm_identifier<D: Digest + AssociatedOid>(
+) -> spki::Result<AlgorithmIdentifierOwned> {
+	let salt_len = <D as Digest>::output_size() as u8;
+
+	Ok(AlgorithmIdentifierOwned {
+		oid: rfc5912::ID_RSASSA_PSS,
+		parameters: Some(Any::encode_from(&RsaPssParams::new::<D>(salt_len))?),
+	})
+}
+
+fn key_algorithm_identifier<D: Digest + AssociatedOid>(
+	restricted: bool,
+) -> spki::Result<AlgorithmIdentifierOwned> {
+	if restricted {
+		return signature_algorithm_identifier::<D>();
+	}
+
+	Ok(AlgorithmIdentifierOwned {
+		oid: rfc5912::RSA_ENCRYPTION,
+		parameters: Some(Any::null()),
+	})
+}
+
+#[cfg(test)]
+mod tests {
+	use der::Encode;
+	use rand_core::OsRng;
+
+	use super::*;
+	use crate::scheme::{load_key, verify_signature};
+
+	fn unparameterized_key() -> Vec<u8> {
+		let private_key = RsaPrivateKey::new(&mut OsRng, 2048).unwrap();
+		let private_key = private_key.to_pkcs1_der().unwrap();
+
+		PrivateKeyInfo::new(
+			AlgorithmIdentifierOwned {
+				oid: rfc5912::ID_RSASSA_PSS,
+				parameters: None,
+			}
+			.owned_to_ref(),
+			private_key.as_bytes(),
+		)
+		.to_der()
+		.unwrap()
+	}
+
+	#[test]
+	fn unparameterized_key_is_unrestricted() {
+		let (strategy, key) = load_key(&unparameterized_key()).unwrap();
+
+		let public_key = key.to_subject_public_key_info().unwrap();
+
+		assert_eq!(public_key.algorithm.oid, rfc5912::ID_RSASSA_PSS);
+		assert_eq!(public_key.algorithm.parameters, None);
+
+		let algorithm = strategy.signature_algorithm_identifier().unwrap();
+		let signature = key.sign_with_rng(b"data", &mut OsRng).unwrap();
+
+		assert!(verify_signature(&algorithm, &public_key, b"data", &signature).is_ok());
+		assert!(verify_signature(&algorithm, &public_key, b"other", &signature).is_err());
+	}
+}
@@ -1,42 +1,166 @@
 use std::time::Duration;
 
+use const_oid::ObjectIdentifier;
 use der::EncodePem;
 use pkcs8::LineEnding;
+use time::OffsetDateTime;
+use x509_cert::ext::pkix::KeyUsage;
 use x509_cert::name::Name;
 
 use common::*;
 
 use super::alt_name::AltName;
-use super::certificate::{gen_tbs_certificate, sign_certificate, OptionsBuilder};
+use super::certificate::{gen_tbs_certificate, sign_certificate, OptionsBuilder, TimeEncoding};
+use super::context::Context;
+use super::custom_extension::CustomExtension;
+use super::duration::CalendarDuration;
+use super::key_id::KeyIdMethod;
+use super::name_constraints::Subtree;
+use super::policy::{CaPolicy, Policy};
 use super::scheme::SignatureStrategy;
+use super::serial::SerialStrategy;
 
 #[derive(Debug, Clone)]
 pub struct SelfSignedCertOptions {
 	pub issuer: Name,
 	pub subject: Name,
-	pub duration: Duration,
+	pub duration: Option<CalendarDuration>,
+	pub not_before: Option<OffsetDateTime>,
+	pub not_after: Option<OffsetDateTime>,
+	pub backdate: Duration,
+	pub time_encoding: TimeEncoding,
+	pub serial: SerialStrategy,
+	pub is_ca: bool,
+	pub path_len_constraint: Option<u8>,
 	pub san: Vec<AltName>,
+	pub key_usage: Option<KeyUsage>,
+	pub key_usage_critical: Option<bool>,
+	pub ext_key_usage: Vec<ObjectIdentifier>,
+	pub permitted_subtrees: Vec<Subtree>,
+	pub excluded_subtrees: Vec<Subtree>,
+	pub policies: Vec<Policy>,
+	pub ca_policy: Option<CaPolicy>,
+	pub ocsp_urls: Vec<String>,
+	pub ca_issuers_urls: Vec<String>,
+	pub crl_urls: Vec<String>,
+	pub custom_extensions: Vec<CustomExtension>,
+	pub key_id_method: KeyIdMethod,
 }
 
 pub fn gen_self_signed(
 	strategy: &dyn SignatureStrategy,
 	options: SelfSignedCertOptions,
 ) -> Result<(String, String)> {
-	let key = strategy.generate_key()?;
+	gen_self_signed_with_context(strategy, options, &mut Context::default())
+}
+
+pub fn gen_self_signed_with_context(
+	strategy: &dyn SignatureStrategy,
+	options: SelfSignedCertOptions,
+	context: &mut Context,
+) -> Result<(String, String)> {
+	let key = strategy.generate_key_with_rng(context.rng())?;
+
+	let mut builder = OptionsBuilder::default()
+		.issuer(options.issuer)
+		.subject(options.subject)
+		.duration(options.duration)
+		.not_before(options.not_before)
+		.not_after(options.not_after)
+		.backdate(options.backdate)
+		.time_encoding(options.time_encoding)
+		.serial(options.serial)
+		.path_len_constraint(options.path_len_constraint)
+		.alt_names(options.san)
+		.key_usage(options.key_usage)
+		.key_usage_critical(options.key_usage_critical)
+		.ext_key_usage(options.ext_key_usage)
+		.permitted_subtrees(options.permitted_subtrees)
+		.excluded_subtrees(options.excluded_subtrees)
+		.policies(options.policies)
+		.ca_policy(options.ca_policy)
+		.ocsp_urls(options.ocsp_urls)
+		.ca_issuers_urls(options.ca_issuers_urls)
+		.crl_urls(options.crl_urls)
+		.custom_extensions(options.custom_extensions)
+		.key_id_method(options.key_id_method);
+
+	if options.is_ca {
+		builder = builder.is_ca();
+	}
 
 	let tbs_cert = gen_tbs_certificate(
 		strategy,
-		key.as_ref(),
-		OptionsBuilder::default()
-			.issuer(options.issuer)
-			.subject(options.subject)
-			.duration(options.duration)
-			.is_ca()
-			.alt_names(options.san)
-			.build()?,
+		key.to_subject_public_key_info()?,
+		builder.build()?,
+		context,
 	)?;
 
-	let crt = sign_certificate(strategy, key.as_ref(), tbs_cert)?;
+	let crt = sign_certificate(strategy, key.as_ref(), tbs_cert, context)?;
 
 	Ok((key.to_pem(LineEnding::LF)?, crt.to_pem(LineEnding::LF)?))
 }
+
+#[cfg(test)]
+mod tests {
+	use std::str::FromStr;
+
+	use der::DecodePem;
+	use x509_cert::Certificate;
+
+	use super::*;
+	use crate::scheme::ed25519::Ed25519;
+
+	fn options(
+		duration: Option<CalendarDuration>,
+		not_after: Option<OffsetDateTime>,
+	) -> SelfSignedCertOptions {
+		SelfSignedCertOptions {
+			issuer: Name::from_str("CN=test").unwrap(),
+			subject: Name::from_str("CN=test").unwrap(),
+			duration,
+			not_before: None,
+			not_after,
+			backdate: Duration::ZERO,
+			time_encoding: TimeEncoding::default(),
+			serial: SerialStrategy::default(),
+			is_ca: false,
+			path_len_constraint: None,
+			san: Vec::new(),
+			key_usage: None,
+			key_usage_critical: None,
+			ext_key_usage: Vec::new(),
+			permitted_subtrees: Vec::new(),
+			excluded_subtrees: Vec::new(),
+			policies: Vec::new(),
+			ca_policy: None,
+			ocsp_urls: Vec::new(),
+			ca_issuers_urls: Vec::new(),
+			crl_urls: Vec::new(),
+			custom_extensions: Vec::new(),
+			key_id_method: KeyIdMethod::default(),
+		}
+	}
+
+	#[test]
+	fn not_after_replaces_duration() {
+		let not_after = OffsetDateTime::from_unix_timestamp(2_208_988_800).unwrap();
+
+		let (_, certificate) = gen_self_signed(&Ed25519, options(None, Some(not_after))).unwrap();
+		let certificate = Certificate::from_pem(certificate).unwrap();
+
+		assert_eq!(
+			certificate
+				.tbs_certificate
+				.validity
+				.not_after
+				.to_unix_duration(),
+			Duration::from_secs(not_after.unix_timestamp() as u64)
+		);
+	}
+
+	#[test]
+	fn duration_or_not_after_is_required() {
+		assert!(gen_self_signed(&Ed25519, options(None, None)).is_err());
+	}
+}
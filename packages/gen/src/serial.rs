@@ -0,0 +1,251 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{ErrorKind, Write};
+use std::path::{Path, PathBuf};
+use std::process;
+use std::thread;
+use std::time::Duration;
+
+use enum_iterator::Sequence;
+use parse_display::Display;
+use x509_cert::serial_number::SerialNumber;
+
+use common::*;
+
+use super::context::Context;
+use super::custom_extension::parse_hex;
+
+const SERIAL_LENGTH: usize = 20;
+const TIMESTAMP_RANDOM_LENGTH: usize = 4;
+const COUNTER_LOCK_ATTEMPTS: u32 = 200;
+const COUNTER_LOCK_RETRY_INTERVAL: Duration = Duration::from_millis(25);
+const COUNTER_LOCK_STALE_AFTER: Duration = Duration::from_secs(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Display, Sequence, Default)]
+pub enum SerialStrategyType {
+	#[default]
+	Random,
+	Counter,
+	Timestamp,
+	Provided,
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+pub enum SerialStrategy {
+	#[default]
+	Random,
+	Counter(PathBuf),
+	Timestamp,
+	Provided(Vec<u8>),
+}
+
+impl SerialStrategy {
+	pub fn parse_provided(hex: &str) -> Result<Self> {
+		let bytes = parse_hex(hex)?;
+
+		to_serial_number(&bytes)?;
+
+		Ok(SerialStrategy::Provided(bytes))
+	}
+
+	pub fn generate(&self, context: &mut Context) -> Result<SerialNumber> {
+		let bytes = match self {
+			SerialStrategy::Random => {
+				let mut bytes = [0u8; SERIAL_LENGTH];
+				context.rng().fill_bytes(&mut bytes);
+
+				bytes[0] = (bytes[0] & 0x7F) | 0x40;
+
+				bytes.to_vec()
+			}
+			SerialStrategy::Counter(path) => next_counter(path)?.to_be_bytes().to_vec(),
+			SerialStrategy::Timestamp => {
+				let mut bytes = context.now().unix_timestamp_nanos().to_be_bytes().to_vec();
+
+				let mut suffix = [0u8; TIMESTAMP_RANDOM_LENGTH];
+				context.rng().fill_bytes(&mut suffix);
+
+				bytes.extend(suffix);
+				bytes
+			}
+			SerialStrategy::Provided(bytes) => bytes.clone(),
+		};
+
+		to_serial_number(&bytes)
+	}
+}
+
+fn next_counter(path: &Path) -> Result<u128> {
+	let _lock = CounterLock::acquire(path)?;
+
+	let current = match fs::read_to_string(path) {
+		Ok(contents) => contents
+			.trim()
+			.parse::<u128>()
+			.map_err(|_| Error::msg(format!("invalid serial counter in {}", path.display())))?,
+		Err(err) if err.kind() == ErrorKind::NotFound => 0,
+		Err(err) => return Err(err.into()),
+	};
+
+	let next = current
+		.checked_add(1)
+		.ok_or_else(|| Error::msg("serial counter overflow"))?;
+
+	let temp_path = sibling_path(path, "tmp");
+
+	let mut file = File::create(&temp_path)?;
+	file.write_all(format!("{next}\n").as_bytes())?;
+	file.sync_all()?;
+
+	fs::rename(&temp_path, path)?;
+
+	#[cfg(unix)]
+	if let Some(parent) = path
+		.parent()
+		.filter(|parent| !parent.as_os_str().is_empty())
+	{
+		File::open(parent)?.sync_all()?;
+	}
+
+	Ok(next)
+}
+
+struct CounterLock(PathBuf);
+
+impl CounterLock {
+	fn acquire(path: &Path) -> Result<Self> {
+		let lock_path = sibling_path(path, "lock");
+
+		for _ in 0..COUNTER_LOCK_ATTEMPTS {
+			match OpenOptions::new()
+				.write(true)
+				.create_new(true)
+				.open(&lock_path)
+			{
+				Ok(mut file) => {
+					let lock = CounterLock(lock_path);
+					writeln!(file, "{}", process::id())?;
+
+					return Ok(lock);
+				}
+				Err(err) if err.kind() == ErrorKind::AlreadyExists => {
+					if is_stale(&lock_path) {
+						match fs::remove_file(&lock_path) {
+							Ok(()) => continue,
+							Err(err) if err.kind() == ErrorKind::NotFound => continue,
+							Err(err) => return Err(err.into()),
+						}
+					}
+
+					thread::sleep(COUNTER_LOCK_RETRY_INTERVAL)
+				}
+				Err(err) => return Err(err.into()),
+			}
+		}
+
+		let owner = fs::read_to_string(&lock_path)
+			.ok()
+			.map(|pid| format!(" held by process {}", pid.trim()))
+			.unwrap_or_default();
+
+		Err(Error::msg(format!(
+			"serial counter is locked by {}{owner}, delete it if no other issuance is running",
+			lock_path.display()
+		)))
+	}
+}
+
+fn is_stale(lock_path: &Path) -> bool {
+	fs::metadata(lock_path)
+		.and_then(|metadata| metadata.modified())
+		.ok()
+		.and_then(|modified| modified.elapsed().ok())
+		.is_some_and(|age| age > COUNTER_LOCK_STALE_AFTER)
+}
+
+impl Drop for CounterLock {
+	fn drop(&mut self) {
+		let _ = fs::remove_file(&self.0);
+	}
+}
+
+fn sibling_path(path: &Path, extension: &str) -> PathBuf {
+	let mut name = path.as_os_str().to_owned();
+	name.push(".");
+	name.push(extension);
+
+	PathBuf::from(name)
+}
+
+fn to_serial_number(bytes: &[u8]) -> Result<SerialNumber> {
+	let first_non_zero = bytes.iter().position(|byte| *byte != 0);
+
+	let Some(first_non_zero) = first_non_zero else {
+		return Err(Error::msg("serial number must be positive"));
+	};
+
+	let bytes = &bytes[first_non_zero..];
+
+	if bytes.len() > SERIAL_LENGTH || (bytes.len() == SERIAL_LENGTH && bytes[0] & 0x80 != 0) {
+		return Err(Error::msg("serial number is longer than 20 octets"));
+	}
+
+	Ok(SerialNumber::new(bytes)?)
+}
+
+#[cfg(test)]
+mod tests {
+	use std::collections::HashSet;
+	use std::time::SystemTime;
+
+	use super::*;
+
+	#[test]
+	fn concurrent_counters_are_unique() {
+		let dir = std::env::temp_dir().join(format!("gen-serial-{}", process::id()));
+		fs::create_dir_all(&dir).unwrap();
+
+		let path = dir.join("counter");
+
+		let threads: Vec<_> = (0..8)
+			.map(|_| {
+				let path = path.clone();
+
+				thread::spawn(move || {
+					(0..25)
+						.map(|_| next_counter(&path).unwrap())
+						.collect::<Vec<_>>()
+				})
+			})
+			.collect();
+
+		let serials: HashSet<u128> = threads
+			.into_iter()
+			.flat_map(|thread| thread.join().unwrap())
+			.collect();
+
+		assert_eq!(serials, (1..=200).collect());
+		assert_eq!(fs::read_to_string(&path).unwrap(), "200\n");
+		assert!(!sibling_path(&path, "lock").exists());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+
+	#[test]
+	fn stale_lock_is_replaced() {
+		let dir = std::env::temp_dir().join(format!("gen-serial-stale-{}", process::id()));
+		fs::create_dir_all(&dir).unwrap();
+
+		let path = dir.join("counter");
+		let lock_path = sibling_path(&path, "lock");
+
+		let lock = File::create(&lock_path).unwrap();
+		lock.set_modified(SystemTime::now() - COUNTER_LOCK_STALE_AFTER * 2)
+			.unwrap();
+		drop(lock);
+
+		assert_eq!(next_counter(&path).unwrap(), 1);
+		assert!(!lock_path.exists());
+
+		fs::remove_dir_all(&dir).unwrap();
+	}
+}
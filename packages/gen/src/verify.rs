@@ -0,0 +1,127 @@
+use const_oid::AssociatedOid;
+use der::{Decode, Encode};
+use time::OffsetDateTime;
+use x509_cert::ext::pkix::{BasicConstraints, KeyUsage, KeyUsages};
+use x509_cert::Certificate;
+
+use common::*;
+
+use super::certificate::to_offset_date_time;
+use super::scheme::verify_signature;
+
+pub fn verify_certificate(certificate: &Certificate, issuer: &Certificate) -> Result<()> {
+	let tbs_certificate = &certificate.tbs_certificate;
+
+	if tbs_certificate.issuer != issuer.tbs_certificate.subject {
+		return Err(Error::msg(format!(
+			"certificate {} was not issued by {}",
+			tbs_certificate.subject, issuer.tbs_certificate.subject
+		)));
+	}
+
+	if tbs_certificate.signature != certificate.signature_algorithm {
+		return Err(Error::msg(format!(
+			"certificate {} has mismatched signature algorithms",
+			tbs_certificate.subject
+		)));
+	}
+
+	verify_signature(
+		&certificate.signature_algorithm,
+		&issuer.tbs_certificate.subject_public_key_info,
+		&tbs_certificate.to_der()?,
+		certificate.signature.raw_bytes(),
+	)
+	.map_err(|err| {
+		err.context(format!(
+			"invalid signature on certificate {}",
+			tbs_certificate.subject
+		))
+	})
+}
+
+pub fn verify_chain(
+	chain: &[Certificate],
+	trust_anchor: &Certificate,
+	time: OffsetDateTime,
+) -> Result<()> {
+	let path: Vec<&Certificate> = chain.iter().chain([trust_anchor]).collect();
+
+	for certificate in &path {
+		check_validity(certificate, time)?;
+	}
+
+	for (index, pair) in path.windows(2).enumerate() {
+		let (certificate, issuer) = (pair[0], pair[1]);
+
+		verify_certificate(certificate, issuer)?;
+		check_issuer_constraints(issuer, index)?;
+	}
+
+	Ok(())
+}
+
+fn check_validity(certificate: &Certificate, time: OffsetDateTime) -> Result<()> {
+	let validity = &certificate.tbs_certificate.validity;
+
+	if time < to_offset_date_time(validity.not_before)? {
+		return Err(Error::msg(format!(
+			"certificate {} is not yet valid",
+			certificate.tbs_certificate.subject
+		)));
+	}
+
+	if time > to_offset_date_time(validity.not_after)? {
+		return Err(Error::msg(format!(
+			"certificate {} has expired",
+			certificate.tbs_certificate.subject
+		)));
+	}
+
+	Ok(())
+}
+
+fn check_issuer_constraints(issuer: &Certificate, intermediates: usize) -> Result<()> {
+	let subject = &issuer.tbs_certificate.subject;
+
+	match find_extension::<BasicConstraints>(issuer)? {
+		Some(basic_constraints) if basic_constraints.ca => {
+			if let Some(path_len) = basic_constraints.path_len_constraint {
+				if intermediates > path_len as usize {
+					return Err(Error::msg(format!(
+						"certificate {subject} exceeds its path length constraint"
+					)));
+				}
+			}
+		}
+		_ => {
+			return Err(Error::msg(format!(
+				"certificate {subject} is not a certificate authority"
+			)))
+		}
+	}
+
+	if let Some(key_usage) = find_extension::<KeyUsage>(issuer)? {
+		if !key_usage.0.contains(KeyUsages::KeyCertSign) {
+			return Err(Error::msg(format!(
+				"certificate {subject} is not allowed to sign certificates"
+			)));
+		}
+	}
+
+	Ok(())
+}
+
+pub(crate) fn find_extension<T>(certificate: &Certificate) -> Result<Option<T>>
+where
+	T: AssociatedOid + for<'a> Decode<'a>,
+{
+	certificate
+		.tbs_certificate
+		.extensions
+		.iter()
+		.flatten()
+		.find(|extension| extension.extn_id == T::OID)
+		.map(|extension| Ok(T::from_der(extension.extn_value.as_bytes())?))
+		.transpose()
+}